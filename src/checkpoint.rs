@@ -0,0 +1,178 @@
+use crate::data::game_desc::GameDesc;
+use crate::search::SeedMatch;
+use crate::transform_rules::Rules;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 64-bit FNV-1a of the serialized rule tree. Stable across builds, unlike
+/// `DefaultHasher`, so checkpoints written by one binary can be resumed by another.
+pub fn hash_rules(rules: &Rules) -> u64 {
+    let bytes = serde_json::to_vec(rules).unwrap();
+    bytes.iter().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ (*byte as u64)).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub game: GameDesc,
+    pub rule_hash: u64,
+    /// Sorted, non-overlapping, half-open seed ranges that are fully evaluated.
+    pub completed: Vec<(i32, i32)>,
+    /// Matches found inside `completed`, sorted by seed.
+    pub matches: Vec<SeedMatch>,
+}
+
+impl Checkpoint {
+    pub fn new(game: GameDesc, rule_hash: u64) -> Self {
+        Self {
+            game,
+            rule_hash,
+            completed: vec![],
+            matches: vec![],
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Option<Self>, Error> {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        serde_json::from_reader(BufReader::new(file))
+            .map(Some)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+
+    /// Writes to a sibling temporary file first so a crash mid-write never
+    /// leaves a truncated checkpoint behind.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let mut tmp_path = path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()?;
+        drop(writer);
+        std::fs::rename(&tmp_path, path)
+    }
+
+    pub fn is_compatible(&self, game: &GameDesc, rule_hash: u64) -> bool {
+        &self.game == game && self.rule_hash == rule_hash
+    }
+
+    pub fn is_completed(&self, seed: i32) -> bool {
+        let index = self.completed.partition_point(|(_, end)| *end <= seed);
+        self.completed
+            .get(index)
+            .is_some_and(|(start, _)| *start <= seed)
+    }
+
+    pub fn find_match(&self, seed: i32) -> Option<&SeedMatch> {
+        self.matches
+            .binary_search_by_key(&seed, |m| m.seed)
+            .ok()
+            .map(|index| &self.matches[index])
+    }
+
+    /// Records `start..end` as evaluated, merging it with adjacent ranges.
+    pub fn record(&mut self, start: i32, end: i32, matches: Vec<SeedMatch>) {
+        if start >= end {
+            return;
+        }
+        let first = self.completed.partition_point(|(_, e)| *e < start);
+        let last = self.completed.partition_point(|(s, _)| *s <= end);
+        let mut merged = (start, end);
+        if first < last {
+            merged.0 = merged.0.min(self.completed[first].0);
+            merged.1 = merged.1.max(self.completed[last - 1].1);
+        }
+        self.completed.splice(first..last, [merged]);
+
+        for m in matches {
+            if let Err(index) = self.matches.binary_search_by_key(&m.seed, |x| x.seed) {
+                self.matches.insert(index, m);
+            }
+        }
+    }
+
+    /// Returns the parts of `start..end` that still need to be evaluated.
+    pub fn remaining(&self, start: i32, end: i32) -> Vec<(i32, i32)> {
+        let mut result = vec![];
+        let mut cursor = start;
+        for &(s, e) in &self.completed {
+            if e <= cursor {
+                continue;
+            }
+            if s >= end {
+                break;
+            }
+            if s > cursor {
+                result.push((cursor, s));
+            }
+            cursor = e;
+        }
+        if cursor < end {
+            result.push((cursor, end));
+        }
+        result
+    }
+}
+
+/// Owns a checkpoint and persists it at most once per `interval`.
+pub struct CheckpointWriter {
+    pub path: PathBuf,
+    pub checkpoint: Checkpoint,
+    interval: Duration,
+    last_saved: Instant,
+}
+
+impl CheckpointWriter {
+    /// Resumes the checkpoint at `path`, or starts a new one if there is none.
+    /// Fails if the existing checkpoint belongs to a different game or rule tree.
+    pub fn open(
+        path: PathBuf,
+        game: &GameDesc,
+        rules: &Rules,
+        interval: Duration,
+    ) -> Result<Self, Error> {
+        let rule_hash = hash_rules(rules);
+        let checkpoint = match Checkpoint::load(&path)? {
+            Some(checkpoint) if checkpoint.is_compatible(game, rule_hash) => checkpoint,
+            Some(_) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Checkpoint {} was written for different game settings or rules",
+                        path.display()
+                    ),
+                ))
+            }
+            None => Checkpoint::new(*game, rule_hash),
+        };
+        Ok(Self {
+            path,
+            checkpoint,
+            interval,
+            last_saved: Instant::now(),
+        })
+    }
+
+    pub fn save(&mut self) -> Result<(), Error> {
+        self.checkpoint.save(&self.path)?;
+        self.last_saved = Instant::now();
+        Ok(())
+    }
+
+    pub fn save_if_due(&mut self) -> Result<(), Error> {
+        if self.last_saved.elapsed() >= self.interval {
+            self.save()
+        } else {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,544 @@
+use crate::checkpoint::CheckpointWriter;
+use crate::cluster::{coordinate, work, LeaseTable};
+use crate::data::game_desc::GameDesc;
+use crate::data::heightmap::Heightmap;
+use crate::data::rule::Rule;
+use crate::export::{export_range, ExportConfig};
+use crate::heightmap::{write_heightmap, HeightmapConfig, HeightmapFormat, HeightmapReport};
+use crate::parity::check_fixtures;
+use crate::profile::{report, transform_rules_adaptive};
+use crate::search::{
+    rank_range, search_range, RankConfig, SearchConfig, SeedMatch, TopK, CHUNK_SIZE, MAX_SEED,
+};
+use crate::server::serve;
+use crate::transform_rules::{transform_rules, Rules};
+use crate::transform_scores::transform_scores;
+use crate::worldgen::galaxy_diff::diff_seeds;
+use crate::worldgen::galaxy_gen::create_galaxy;
+use rayon::ThreadPoolBuilder;
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const USAGE: &str = "Usage:
+  dsp_seed [--themes <file>] <command> ...
+  dsp_seed search --config <file> [--start <seed>] [--end <seed>] [--threads <n>] [--output <file>] [--checkpoint <file>] [--checkpoint-interval <secs>] [--adaptive <true|false>]
+  dsp_seed coordinate --config <file> [--start <seed>] [--end <seed>] [--bind <addr>] [--lease <seeds>] [--lease-timeout <secs>] [--output <file>] [--checkpoint <file>] [--checkpoint-interval <secs>]
+  dsp_seed work --coordinator <host:port> [--threads <n>]
+  dsp_seed export --config <file> --output <dir> [--start <seed>] [--end <seed>] [--threads <n>]
+  dsp_seed diff --from <seed> [--to <seed>] [--game <file>] [--to-game <file>] [--output <file>]
+  dsp_seed heightmap --seed <seed> --star <index> --planet <index> [--config <file>] [--star-count <n>] [--width <px>] [--format <png|raw>] [--output <file>]
+  dsp_seed profile --config <file> [--start <seed>] [--end <seed>] [--threads <n>] [--output <file>]
+  dsp_seed parity --fixtures <dir> [--output <file>]
+  dsp_seed rank --config <file> [--top <n>] [--start <seed>] [--end <seed>] [--threads <n>] [--output <file>]";
+
+/// Default address of `coordinate`, one above the WebSocket server.
+const COORDINATOR_BIND: &str = "127.0.0.1:62880";
+
+/// Runs the subcommand named by the first argument, or the WebSocket server
+/// when there is none.
+pub fn run(args: &[String]) -> Result<(), Error> {
+    let args = load_themes(args)?;
+    match args.first().map(String::as_str) {
+        None | Some("serve") => serve(),
+        Some("search") => run_search(&args[1..]),
+        Some("rank") => run_rank(&args[1..]),
+        Some("profile") => run_profile(&args[1..]),
+        Some("export") => run_export(&args[1..]),
+        Some("diff") => run_diff(&args[1..]),
+        Some("heightmap") => run_heightmap(&args[1..]),
+        Some("coordinate") => run_coordinate(&args[1..]),
+        Some("work") => run_work(&args[1..]),
+        Some("parity") => run_parity(&args[1..]),
+        Some(command) => Err(invalid_input(format!(
+            "Unknown command: {}\nUsage: dsp_seed [serve | search | rank | profile | export | diff | heightmap | coordinate | work | parity]",
+            command
+        ))),
+    }
+}
+
+/// Loads the theme protos of the leading `--themes <file>` and returns the
+/// arguments after them.
+fn load_themes(args: &[String]) -> Result<&[String], Error> {
+    let [flag, path, rest @ ..] = args else {
+        return Ok(args);
+    };
+    if flag != "--themes" {
+        return Ok(args);
+    }
+    let json = std::fs::read_to_string(path)?;
+    crate::load_theme_protos(&json)
+        .map_err(|e| invalid_input(format!("Invalid theme protos in {}: {}", path, e)))?;
+    Ok(rest)
+}
+
+pub fn invalid_input(message: impl Into<String>) -> Error {
+    Error::new(ErrorKind::InvalidInput, message.into())
+}
+
+/// Minimal `--flag value` parser shared by the subcommands.
+pub struct Args {
+    pairs: Vec<(String, String)>,
+}
+
+impl Args {
+    pub fn parse(args: &[String]) -> Result<Self, Error> {
+        let mut pairs = vec![];
+        let mut iter = args.iter();
+        while let Some(flag) = iter.next() {
+            let Some(name) = flag.strip_prefix("--") else {
+                return Err(invalid_input(format!("Unexpected argument: {}", flag)));
+            };
+            let Some(value) = iter.next() else {
+                return Err(invalid_input(format!("Missing value for --{}", name)));
+            };
+            pairs.push((name.to_owned(), value.to_owned()));
+        }
+        Ok(Self { pairs })
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn require(&self, name: &str) -> Result<&str, Error> {
+        self.get(name)
+            .ok_or_else(|| invalid_input(format!("Missing required --{}\n{}", name, USAGE)))
+    }
+
+    pub fn parse_required<T: std::str::FromStr>(&self, name: &str) -> Result<T, Error> {
+        let value = self.require(name)?;
+        value
+            .parse()
+            .map_err(|_| invalid_input(format!("Invalid value for --{}: {}", name, value)))
+    }
+
+    pub fn parse_or<T: std::str::FromStr>(&self, name: &str, default: T) -> Result<T, Error> {
+        match self.get(name) {
+            Some(value) => value
+                .parse()
+                .map_err(|_| invalid_input(format!("Invalid value for --{}: {}", name, value))),
+            None => Ok(default),
+        }
+    }
+}
+
+pub fn read_json<T: serde::de::DeserializeOwned>(path: &str) -> Result<T, Error> {
+    let file = File::open(path)?;
+    serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| invalid_input(format!("Failed to parse {}: {}", path, e)))
+}
+
+pub fn open_output(path: Option<&str>) -> Result<Box<dyn Write>, Error> {
+    Ok(match path {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(std::io::stdout())),
+    })
+}
+
+pub fn parse_seed_range(args: &Args) -> Result<(i32, i32), Error> {
+    let start: i32 = args.parse_or("start", 0)?;
+    let end: i32 = args.parse_or("end", MAX_SEED)?;
+    check_seed_range(start, end)
+}
+
+fn check_seed_range(start: i32, end: i32) -> Result<(i32, i32), Error> {
+    if start < 0 || end > MAX_SEED || start >= end {
+        return Err(invalid_input(format!(
+            "Invalid seed range {}..{}, expected 0 <= start < end <= {}",
+            start, end, MAX_SEED
+        )));
+    }
+    Ok((start, end))
+}
+
+fn check_game(game: &GameDesc) -> Result<(), Error> {
+    game.validate().map_err(invalid_input)
+}
+
+fn write_matches(output: &mut dyn Write, matches: &[SeedMatch]) -> Result<(), Error> {
+    for m in matches {
+        serde_json::to_writer(&mut *output, m)?;
+        output.write_all(b"\n")?;
+    }
+    output.flush()
+}
+
+/// With `adaptive`, `And`/`Or` children are re-ordered after every chunk
+/// from their measured cost and selectivity instead of the static priorities.
+fn build_rule(rule: Rules, adaptive: bool) -> Box<dyn Rule + Send + Sync> {
+    if adaptive {
+        transform_rules_adaptive(rule, &mut vec![], 0)
+    } else {
+        transform_rules(rule)
+    }
+}
+
+fn build_pool(args: &Args) -> Result<rayon::ThreadPool, Error> {
+    let threads: usize = args.parse_or("threads", 0)?;
+    ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .map_err(|e| invalid_input(e.to_string()))
+}
+
+/// The parts of `start..end` the checkpoint has not covered yet, and how
+/// many matches it already has in the range.
+fn resume_ranges(
+    checkpoint: &Option<CheckpointWriter>,
+    start: i32,
+    end: i32,
+    output: &mut dyn Write,
+) -> Result<(Vec<(i32, i32)>, usize), Error> {
+    let Some(writer) = checkpoint else {
+        return Ok((vec![(start, end)], 0));
+    };
+    // Re-emit what was found before the restart so the output is complete.
+    let previous: Vec<SeedMatch> = writer
+        .checkpoint
+        .matches
+        .iter()
+        .filter(|m| start <= m.seed && m.seed < end)
+        .cloned()
+        .collect();
+    write_matches(output, &previous)?;
+    if !previous.is_empty() {
+        eprintln!(
+            "Resumed from checkpoint with {} seeds found",
+            previous.len()
+        );
+    }
+    Ok((writer.checkpoint.remaining(start, end), previous.len()))
+}
+
+pub fn run_search(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let config: SearchConfig = read_json(args.require("config")?)?;
+    check_game(&config.game)?;
+    let (start, end) = parse_seed_range(&args)?;
+    let interval: u64 = args.parse_or("checkpoint-interval", 60)?;
+    let pool = build_pool(&args)?;
+    let mut checkpoint = match args.get("checkpoint") {
+        Some(path) => Some(CheckpointWriter::open(
+            PathBuf::from(path),
+            &config.game,
+            &config.rule,
+            Duration::from_secs(interval),
+        )?),
+        None => None,
+    };
+    let rule = build_rule(config.rule, args.parse_or("adaptive", false)?);
+    let mut output = open_output(args.get("output"))?;
+
+    let (ranges, mut found) = resume_ranges(&checkpoint, start, end, &mut output)?;
+
+    eprintln!(
+        "Searching seeds {}..{} on {} threads",
+        start,
+        end,
+        pool.current_num_threads()
+    );
+    for (range_start, range_end) in ranges {
+        search_range(
+            &pool,
+            &config.game,
+            rule.as_ref(),
+            range_start,
+            range_end,
+            |chunk_start, chunk_end, matches| {
+                write_matches(&mut output, &matches)?;
+                found += matches.len();
+                if let Some(writer) = &mut checkpoint {
+                    writer.checkpoint.record(chunk_start, chunk_end, matches);
+                    writer.save_if_due()?;
+                }
+                eprintln!("Searched up to {}, {} found", chunk_end, found);
+                Ok::<(), Error>(())
+            },
+        )?;
+    }
+    if let Some(writer) = &mut checkpoint {
+        writer.save()?;
+    }
+    eprintln!("Done. {} seeds found.", found);
+    Ok(())
+}
+
+pub fn run_coordinate(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let config: SearchConfig = read_json(args.require("config")?)?;
+    check_game(&config.game)?;
+    let (start, end) = parse_seed_range(&args)?;
+    let lease_size: i32 = args.parse_or("lease", CHUNK_SIZE * 10)?;
+    if lease_size <= 0 {
+        return Err(invalid_input("--lease must be positive"));
+    }
+    let timeout = Duration::from_secs(args.parse_or("lease-timeout", 60)?);
+    let interval: u64 = args.parse_or("checkpoint-interval", 60)?;
+    let bind = args.get("bind").unwrap_or(COORDINATOR_BIND);
+    let mut checkpoint = match args.get("checkpoint") {
+        Some(path) => Some(CheckpointWriter::open(
+            PathBuf::from(path),
+            &config.game,
+            &config.rule,
+            Duration::from_secs(interval),
+        )?),
+        None => None,
+    };
+    let mut output = open_output(args.get("output"))?;
+
+    let (ranges, mut found) = resume_ranges(&checkpoint, start, end, &mut output)?;
+    let leases = LeaseTable::new(&ranges, lease_size, timeout);
+
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async {
+        let listener = tokio::net::TcpListener::bind(bind).await?;
+        eprintln!(
+            "Coordinating seeds {}..{} in {} leases on {}",
+            start,
+            end,
+            leases.len(),
+            listener.local_addr()?
+        );
+        coordinate(listener, &config, leases, |report| {
+            write_matches(&mut output, &report.matches)?;
+            found += report.matches.len();
+            if let Some(writer) = &mut checkpoint {
+                writer
+                    .checkpoint
+                    .record(report.start, report.end, report.matches);
+                writer.save_if_due()?;
+            }
+            eprintln!(
+                "Searched {}..{}, {} found, {} leases left",
+                report.start, report.end, found, report.remaining
+            );
+            Ok(())
+        })
+        .await
+    })?;
+    if let Some(writer) = &mut checkpoint {
+        writer.save()?;
+    }
+    eprintln!("Done. {} seeds found.", found);
+    Ok(())
+}
+
+pub fn run_work(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let url = format!("ws://{}", args.require("coordinator")?);
+    let pool = build_pool(&args)?;
+    eprintln!(
+        "Working for {} on {} threads",
+        url,
+        pool.current_num_threads()
+    );
+    let runtime = tokio::runtime::Runtime::new()?;
+    let searched = runtime.block_on(work(&url, pool))?;
+    eprintln!("Done. {} leases searched.", searched);
+    Ok(())
+}
+
+pub fn run_rank(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let config: RankConfig = read_json(args.require("config")?)?;
+    check_game(&config.game)?;
+    let (start, end) = parse_seed_range(&args)?;
+    let top_count: usize = args.parse_or("top", 100)?;
+    let pool = build_pool(&args)?;
+    let rule = config.rule.map(transform_rules);
+    let score = transform_scores(config.score);
+    let mut top = TopK::new(top_count);
+
+    eprintln!(
+        "Ranking seeds {}..{} on {} threads",
+        start,
+        end,
+        pool.current_num_threads()
+    );
+    rank_range(
+        &pool,
+        &config.game,
+        rule.as_deref(),
+        score.as_ref(),
+        start..end,
+        &mut top,
+        |chunk_end, top| {
+            match top.to_sorted_vec().first() {
+                Some(best) => eprintln!(
+                    "Ranked up to {}, best seed {} ({})",
+                    chunk_end, best.seed, best.score
+                ),
+                None => eprintln!("Ranked up to {}", chunk_end),
+            }
+            Ok::<(), Error>(())
+        },
+    )?;
+
+    let mut output = open_output(args.get("output"))?;
+    for item in top.to_sorted_vec() {
+        serde_json::to_writer(&mut output, &item)?;
+        output.write_all(b"\n")?;
+    }
+    output.flush()
+}
+
+/// Evaluates a sample of seeds with every `And`/`Or` child profiled, then
+/// prints the measured cost and pass rate of each child in rule-tree order.
+pub fn run_profile(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let config: SearchConfig = read_json(args.require("config")?)?;
+    check_game(&config.game)?;
+    let start: i32 = args.parse_or("start", 0)?;
+    let end: i32 = args.parse_or("end", start.saturating_add(CHUNK_SIZE))?;
+    let (start, end) = check_seed_range(start, end)?;
+    let pool = build_pool(&args)?;
+    let mut entries = vec![];
+    let rule = transform_rules_adaptive(config.rule, &mut entries, 0);
+
+    eprintln!(
+        "Profiling seeds {}..{} on {} threads",
+        start,
+        end,
+        pool.current_num_threads()
+    );
+    search_range(&pool, &config.game, rule.as_ref(), start, end, |_, _, _| {
+        Ok::<(), Error>(())
+    })?;
+
+    let mut output = open_output(args.get("output"))?;
+    for item in report(&entries) {
+        serde_json::to_writer(&mut output, &item)?;
+        output.write_all(b"\n")?;
+    }
+    output.flush()
+}
+
+pub fn run_export(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let config: ExportConfig = read_json(args.require("config")?)?;
+    check_game(&config.game)?;
+    let dir = PathBuf::from(args.require("output")?);
+    let (start, end) = parse_seed_range(&args)?;
+    let pool = build_pool(&args)?;
+    let rule = config.rule.map(transform_rules);
+
+    eprintln!(
+        "Exporting seeds {}..{} to {} on {} threads",
+        start,
+        end,
+        dir.display(),
+        pool.current_num_threads()
+    );
+    export_range(
+        &pool,
+        &config.game,
+        rule.as_deref(),
+        start,
+        end,
+        &dir,
+        |chunk_end, exported| eprintln!("Exported up to {}, {} seeds written", chunk_end, exported),
+    )
+}
+
+/// Renders the terrain of one planet and prints its land and ocean fraction.
+/// The galaxy comes from the `game` of `--config`, with `--star-count` on
+/// top, or from the game's default settings.
+pub fn run_heightmap(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let seed: i32 = args.parse_required("seed")?;
+    check_seed_range(seed, seed.saturating_add(1))?;
+    let star_index: usize = args.parse_required("star")?;
+    let planet_index: usize = args.parse_required("planet")?;
+    let width: usize = args.parse_or("width", 512)?;
+    let format: HeightmapFormat = args.parse_or("format", HeightmapFormat::Png)?;
+    let mut game = match args.get("config") {
+        Some(path) => read_json::<HeightmapConfig>(path)?.game,
+        None => GameDesc::default(),
+    };
+    game.star_count = args.parse_or("star-count", game.star_count)?;
+    check_game(&game)?;
+    if width < 2 {
+        return Err(invalid_input("--width must be at least 2"));
+    }
+
+    let habitable_count = Cell::new(0_i32);
+    let galaxy = create_galaxy(seed, &game, &habitable_count);
+    let planet = galaxy
+        .stars
+        .get(star_index)
+        .and_then(|sp| sp.get_planets().get(planet_index))
+        .ok_or_else(|| {
+            invalid_input(format!(
+                "Seed {} has no planet {} at star {}",
+                seed, planet_index, star_index
+            ))
+        })?;
+    let heightmap =
+        Heightmap::new(planet, width).ok_or_else(|| invalid_input("Gas giants have no terrain"))?;
+    if let Some(path) = args.get("output") {
+        write_heightmap(&heightmap, format, &PathBuf::from(path))?;
+    }
+
+    let mut output = open_output(None)?;
+    serde_json::to_writer(
+        &mut output,
+        &HeightmapReport::new(seed, star_index, planet, &heightmap),
+    )?;
+    output.write_all(b"\n")?;
+    output.flush()
+}
+
+/// Compares two seeds, or one seed under two game settings. `--to-game`
+/// defaults to `--game`, which defaults to the game's default settings.
+pub fn run_diff(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let from_seed: i32 = args.parse_required("from")?;
+    let to_seed: i32 = args.parse_or("to", from_seed)?;
+    check_seed_range(from_seed, from_seed.saturating_add(1))?;
+    check_seed_range(to_seed, to_seed.saturating_add(1))?;
+    let from_game: GameDesc = match args.get("game") {
+        Some(path) => read_json(path)?,
+        None => GameDesc::default(),
+    };
+    let to_game: GameDesc = match args.get("to-game") {
+        Some(path) => read_json(path)?,
+        None => from_game,
+    };
+    check_game(&from_game)?;
+    check_game(&to_game)?;
+
+    let diff = diff_seeds(from_seed, &from_game, to_seed, &to_game);
+    let mut output = open_output(args.get("output"))?;
+    serde_json::to_writer(&mut output, &diff)?;
+    output.write_all(b"\n")?;
+    output.flush()
+}
+
+/// Compares `create_galaxy` with the galaxy dumps in `--fixtures`, writing
+/// the first divergence of every seed. Fails when any seed diverges.
+pub fn run_parity(args: &[String]) -> Result<(), Error> {
+    let args = Args::parse(args)?;
+    let reports = check_fixtures(Path::new(args.require("fixtures")?))?;
+    let mut output = open_output(args.get("output"))?;
+    for report in &reports {
+        serde_json::to_writer(&mut output, report)?;
+        output.write_all(b"\n")?;
+    }
+    output.flush()?;
+    let diverged = reports.iter().filter(|r| r.divergence.is_some()).count();
+    if diverged > 0 {
+        return Err(Error::other(format!(
+            "{} of {} seeds diverge",
+            diverged,
+            reports.len()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,412 @@
+use crate::cli::invalid_input;
+use crate::data::game_desc::GameDesc;
+use crate::search::{search_range, SearchConfig, SeedMatch};
+use crate::transform_rules::{transform_rules, Rules};
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use rayon::ThreadPool;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Error;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio_tungstenite::tungstenite::{self, Message};
+use tokio_tungstenite::{accept_async, connect_async};
+
+/// Workers wait this long before asking again when every lease is taken.
+const WAIT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A worker started before its coordinator keeps trying for a while.
+const CONNECT_ATTEMPTS: u32 = 30;
+const CONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// How long a finished coordinator keeps telling workers that it is done.
+const FINISH_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lease {
+    pub id: u64,
+    pub start: i32,
+    pub end: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum WorkerMessage {
+    Hello {
+        threads: usize,
+    },
+    Request,
+    /// Sent while searching, so the lease is not handed to another worker.
+    Heartbeat {
+        lease: u64,
+    },
+    Report {
+        lease: u64,
+        matches: Vec<SeedMatch>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum CoordinatorMessage {
+    #[serde(rename_all = "camelCase")]
+    Config {
+        game: GameDesc,
+        rule: Rules,
+        heartbeat_ms: u64,
+    },
+    Lease(Lease),
+    /// Every lease is taken, but some may still time out.
+    Wait,
+    Done,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActiveLease {
+    start: i32,
+    end: i32,
+    worker: u64,
+    deadline: Instant,
+}
+
+/// Seed ranges of a distributed search and the workers they are leased to.
+///
+/// A lease that was not renewed within `timeout` goes to the next worker
+/// asking for one, keeping its id, so whichever worker reports first wins.
+#[derive(Debug)]
+pub struct LeaseTable {
+    pending: VecDeque<(i32, i32)>,
+    active: BTreeMap<u64, ActiveLease>,
+    next_id: u64,
+    timeout: Duration,
+}
+
+impl LeaseTable {
+    /// Splits `ranges` into leases of at most `size` seeds.
+    pub fn new(ranges: &[(i32, i32)], size: i32, timeout: Duration) -> Self {
+        let size = size.max(1);
+        let mut pending = VecDeque::new();
+        for &(start, end) in ranges {
+            let mut lease_start = start;
+            while lease_start < end {
+                let lease_end = lease_start.saturating_add(size).min(end);
+                pending.push_back((lease_start, lease_end));
+                lease_start = lease_end;
+            }
+        }
+        Self {
+            pending,
+            active: BTreeMap::new(),
+            next_id: 0,
+            timeout,
+        }
+    }
+
+    /// Number of leases not completed yet.
+    pub fn len(&self) -> usize {
+        self.pending.len() + self.active.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Hands out an expired lease, or else the next pending one.
+    pub fn issue(&mut self, worker: u64, now: Instant) -> Option<Lease> {
+        let deadline = now + self.timeout;
+        let expired = self
+            .active
+            .iter_mut()
+            .find(|(_, lease)| lease.deadline <= now);
+        if let Some((&id, lease)) = expired {
+            lease.worker = worker;
+            lease.deadline = deadline;
+            return Some(Lease {
+                id,
+                start: lease.start,
+                end: lease.end,
+            });
+        }
+        let (start, end) = self.pending.pop_front()?;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.active.insert(
+            id,
+            ActiveLease {
+                start,
+                end,
+                worker,
+                deadline,
+            },
+        );
+        Some(Lease { id, start, end })
+    }
+
+    /// Extends the lease if `worker` still holds it.
+    pub fn renew(&mut self, id: u64, worker: u64, now: Instant) -> bool {
+        match self.active.get_mut(&id) {
+            Some(lease) if lease.worker == worker => {
+                lease.deadline = now + self.timeout;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the seed range of the lease the first time it is completed.
+    pub fn complete(&mut self, id: u64) -> Option<(i32, i32)> {
+        self.active
+            .remove(&id)
+            .map(|lease| (lease.start, lease.end))
+    }
+
+    /// Lets the leases of a disconnected worker be issued again right away.
+    pub fn release(&mut self, worker: u64, now: Instant) {
+        for lease in self.active.values_mut() {
+            if lease.worker == worker {
+                lease.deadline = now;
+            }
+        }
+    }
+}
+
+/// Matches of a completed lease, `remaining` being the leases still to go.
+#[derive(Debug, Clone)]
+pub struct LeaseReport {
+    pub start: i32,
+    pub end: i32,
+    pub matches: Vec<SeedMatch>,
+    pub remaining: usize,
+}
+
+async fn send<S>(write: &mut S, message: &impl Serialize) -> Result<(), Error>
+where
+    S: Sink<Message, Error = tungstenite::Error> + Unpin,
+{
+    let text = serde_json::to_string(message)?;
+    write
+        .send(Message::Text(text.into()))
+        .await
+        .map_err(Error::other)
+}
+
+/// Next message of the peer, `None` once it closed the connection.
+async fn recv<S, T>(read: &mut S) -> Result<Option<T>, Error>
+where
+    S: Stream<Item = Result<Message, tungstenite::Error>> + Unpin,
+    T: DeserializeOwned,
+{
+    while let Some(msg) = read.next().await {
+        match msg.map_err(Error::other)? {
+            Message::Text(text) => {
+                return serde_json::from_str(&text)
+                    .map(Some)
+                    .map_err(|e| invalid_input(format!("Unexpected message: {}", e)));
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Serves workers on `listener` until every lease of `leases` was reported,
+/// calling `on_report` once per lease in the order they complete.
+pub async fn coordinate<F>(
+    listener: TcpListener,
+    config: &SearchConfig,
+    leases: LeaseTable,
+    mut on_report: F,
+) -> Result<(), Error>
+where
+    F: FnMut(LeaseReport) -> Result<(), Error>,
+{
+    let mut remaining = leases.len();
+    let hello = CoordinatorMessage::Config {
+        game: config.game,
+        rule: config.rule.clone(),
+        heartbeat_ms: (leases.get_timeout() / 3).as_millis() as u64,
+    };
+    let hello = Arc::new(hello);
+    let table = Arc::new(Mutex::new(leases));
+    let (tx, mut rx) = mpsc::channel::<LeaseReport>(100);
+    let mut workers = JoinSet::new();
+    let mut next_worker = 0;
+    while remaining > 0 {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, addr) = match accepted {
+                    Ok(accepted) => accepted,
+                    Err(e) => {
+                        eprintln!("Warning: Failed to accept a worker: {}", e);
+                        continue;
+                    }
+                };
+                let worker = next_worker;
+                next_worker += 1;
+                eprintln!("Worker {} connected from {}", worker, addr);
+                let hello = Arc::clone(&hello);
+                let table = Arc::clone(&table);
+                let tx = tx.clone();
+                workers.spawn(async move {
+                    if let Err(e) = serve_worker(stream, worker, &hello, &table, &tx).await {
+                        eprintln!("Warning: Worker {} failed: {}", worker, e);
+                    }
+                    table.lock().unwrap().release(worker, Instant::now());
+                    eprintln!("Worker {} disconnected", worker);
+                });
+            }
+            Some(mut report) = rx.recv() => {
+                remaining -= 1;
+                report.remaining = remaining;
+                on_report(report)?;
+            }
+        }
+    }
+    // Workers still connected are told `Done` on their next request.
+    let _ = tokio::time::timeout(FINISH_TIMEOUT, workers.join_all()).await;
+    Ok(())
+}
+
+async fn serve_worker(
+    stream: TcpStream,
+    worker: u64,
+    hello: &CoordinatorMessage,
+    table: &Mutex<LeaseTable>,
+    reports: &mpsc::Sender<LeaseReport>,
+) -> Result<(), Error> {
+    let ws_stream = accept_async(stream).await.map_err(Error::other)?;
+    let (mut write, mut read) = ws_stream.split();
+    while let Some(msg) = recv(&mut read).await? {
+        match msg {
+            WorkerMessage::Hello { threads } => {
+                eprintln!("Worker {} searches on {} threads", worker, threads);
+                send(&mut write, hello).await?;
+            }
+            WorkerMessage::Request => {
+                let reply = {
+                    let mut table = table.lock().unwrap();
+                    match table.issue(worker, Instant::now()) {
+                        Some(lease) => CoordinatorMessage::Lease(lease),
+                        None if table.is_empty() => CoordinatorMessage::Done,
+                        None => CoordinatorMessage::Wait,
+                    }
+                };
+                send(&mut write, &reply).await?;
+            }
+            WorkerMessage::Heartbeat { lease } => {
+                table.lock().unwrap().renew(lease, worker, Instant::now());
+            }
+            WorkerMessage::Report { lease, matches } => {
+                let range = table.lock().unwrap().complete(lease);
+                if let Some((start, end)) = range {
+                    let report = LeaseReport {
+                        start,
+                        end,
+                        matches,
+                        remaining: 0,
+                    };
+                    let _ = reports.send(report).await;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Searches leases from the coordinator at `url` on `pool` until it is done
+/// or goes away. Returns the number of leases searched.
+pub async fn work(url: &str, pool: ThreadPool) -> Result<usize, Error> {
+    let mut attempt = 1;
+    let ws_stream = loop {
+        match connect_async(url).await {
+            Ok((ws_stream, _)) => break ws_stream,
+            Err(e) if attempt < CONNECT_ATTEMPTS => {
+                eprintln!("Waiting for the coordinator at {}: {}", url, e);
+                attempt += 1;
+                tokio::time::sleep(CONNECT_DELAY).await;
+            }
+            Err(e) => return Err(Error::other(e)),
+        }
+    };
+    let (mut write, mut read) = ws_stream.split();
+    let threads = pool.current_num_threads();
+    send(&mut write, &WorkerMessage::Hello { threads }).await?;
+    let Some(CoordinatorMessage::Config {
+        game,
+        rule,
+        heartbeat_ms,
+    }) = recv(&mut read).await?
+    else {
+        return Err(invalid_input("Expected the search config first"));
+    };
+    game.validate().map_err(invalid_input)?;
+    let rule = Arc::new(transform_rules(rule));
+    let pool = Arc::new(pool);
+    let heartbeat = Duration::from_millis(heartbeat_ms.max(1));
+
+    let mut searched = 0;
+    loop {
+        send(&mut write, &WorkerMessage::Request).await?;
+        let lease = match recv(&mut read).await? {
+            Some(CoordinatorMessage::Lease(lease)) => lease,
+            Some(CoordinatorMessage::Wait) => {
+                tokio::time::sleep(WAIT_INTERVAL).await;
+                continue;
+            }
+            Some(CoordinatorMessage::Done) | None => break,
+            Some(CoordinatorMessage::Config { .. }) => {
+                return Err(invalid_input("Unexpected search config"));
+            }
+        };
+        let search = {
+            let pool = Arc::clone(&pool);
+            let rule = Arc::clone(&rule);
+            tokio::task::spawn_blocking(move || {
+                let mut matches = vec![];
+                search_range(
+                    &pool,
+                    &game,
+                    rule.as_ref().as_ref(),
+                    lease.start,
+                    lease.end,
+                    |_, _, chunk| {
+                        matches.extend(chunk);
+                        Ok::<(), Error>(())
+                    },
+                )
+                .map(|_| matches)
+            })
+        };
+        tokio::pin!(search);
+        let mut ticker = tokio::time::interval(heartbeat);
+        ticker.tick().await;
+        let matches = loop {
+            tokio::select! {
+                result = &mut search => break result.map_err(Error::other)??,
+                _ = ticker.tick() => {
+                    send(&mut write, &WorkerMessage::Heartbeat { lease: lease.id }).await?;
+                }
+            }
+        };
+        send(
+            &mut write,
+            &WorkerMessage::Report {
+                lease: lease.id,
+                matches,
+            },
+        )
+        .await?;
+        searched += 1;
+    }
+    let _ = write.close().await;
+    Ok(searched)
+}
@@ -0,0 +1,96 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Cancels, pauses and counts the search batches of one connection or session.
+///
+/// Every batch holds a `CancelToken` of the generation it started in, and
+/// `cancel` moves on to the next generation, so batches sent afterwards run
+/// normally while the older ones wind down.
+#[derive(Debug, Default)]
+pub struct SearchControl {
+    generation: AtomicU64,
+    paused: AtomicBool,
+    processed: AtomicU64,
+}
+
+impl SearchControl {
+    pub fn token(self: &Arc<Self>) -> CancelToken {
+        CancelToken {
+            control: Arc::clone(self),
+            generation: self.generation.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Stops every batch started so far. Also lifts a pause.
+    pub fn cancel(&self) {
+        self.generation.fetch_add(1, Ordering::SeqCst);
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    /// The scheduler holds back the jobs of a paused search, while the ones
+    /// already running finish.
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Seeds evaluated since the connection opened.
+    pub fn get_processed(&self) -> u64 {
+        self.processed.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelToken {
+    control: Arc<SearchControl>,
+    generation: u64,
+}
+
+impl CancelToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.control.generation.load(Ordering::SeqCst) != self.generation
+    }
+
+    pub fn record(&self, seeds: u64) {
+        self.control.processed.fetch_add(seeds, Ordering::Relaxed);
+    }
+}
+
+/// Turns the processed count of a `SearchControl` into a rate.
+#[derive(Debug, Clone, Copy)]
+pub struct Throughput {
+    at: Instant,
+    processed: u64,
+    active: bool,
+}
+
+impl Throughput {
+    pub fn new(control: &SearchControl) -> Self {
+        Self {
+            at: Instant::now(),
+            processed: control.get_processed(),
+            active: false,
+        }
+    }
+
+    /// Seeds processed so far and per second since the last tick. `None` if
+    /// no seed was evaluated since the tick before that either.
+    pub fn tick(&mut self, control: &SearchControl) -> Option<(u64, f64)> {
+        let at = Instant::now();
+        let processed = control.get_processed();
+        let delta = processed - self.processed;
+        let seconds = (at - self.at).as_secs_f64().max(1e-3);
+        let was_active = std::mem::replace(&mut self.active, delta > 0);
+        self.at = at;
+        self.processed = processed;
+        (delta > 0 || was_active).then(|| (processed, delta as f64 / seconds))
+    }
+}
@@ -0,0 +1,159 @@
+use super::game_desc::MAX_STAR_COUNT;
+use serde::ser::{Serialize, SerializeSeq, Serializer};
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, Not};
+
+/// Words needed for one bit per star of the largest galaxy.
+pub const STAR_WORDS: usize = MAX_STAR_COUNT.div_ceil(64);
+
+/// One bit per star, what rules evaluate to.
+pub type StarMask = Bitset<STAR_WORDS>;
+
+/// One bit per planet of a star, what planet rules evaluate to.
+pub type PlanetMask = Bitset<1>;
+
+/// Set of indexes below `64 * W`, stored as `W` words.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Bitset<const W: usize>([u64; W]);
+
+impl<const W: usize> Default for Bitset<W> {
+    fn default() -> Self {
+        Self([0; W])
+    }
+}
+
+impl<const W: usize> Bitset<W> {
+    pub const BITS: usize = W * 64;
+
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The indexes `0..len`.
+    pub fn first(len: usize) -> Self {
+        debug_assert!(len <= Self::BITS);
+        let mut words = [0; W];
+        for (i, word) in words.iter_mut().enumerate() {
+            let bits = len.saturating_sub(i * 64).min(64);
+            *word = if bits == 64 {
+                u64::MAX
+            } else {
+                (1 << bits) - 1
+            };
+        }
+        Self(words)
+    }
+
+    #[inline]
+    pub fn contains(&self, index: usize) -> bool {
+        (self.0[index / 64] & (1 << (index % 64))) != 0
+    }
+
+    #[inline]
+    pub fn insert(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&word| word == 0)
+    }
+
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.0.iter().map(|word| word.count_ones()).sum()
+    }
+
+    /// One past the highest index in the set, 0 if it is empty.
+    #[inline]
+    pub fn get_end(&self) -> usize {
+        self.0
+            .iter()
+            .rposition(|&word| word != 0)
+            .map_or(0, |i| i * 64 + 64 - self.0[i].leading_zeros() as usize)
+    }
+
+    /// The indexes in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+impl<const W: usize> FromIterator<usize> for Bitset<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for index in iter {
+            set.insert(index);
+        }
+        set
+    }
+}
+
+impl<const W: usize> BitAnd for Bitset<W> {
+    type Output = Self;
+
+    #[inline]
+    fn bitand(mut self, rhs: Self) -> Self {
+        self &= rhs;
+        self
+    }
+}
+
+impl<const W: usize> BitAndAssign for Bitset<W> {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0) {
+            *a &= b;
+        }
+    }
+}
+
+impl<const W: usize> BitOr for Bitset<W> {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+impl<const W: usize> BitOrAssign for Bitset<W> {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        for (a, b) in self.0.iter_mut().zip(rhs.0) {
+            *a |= b;
+        }
+    }
+}
+
+impl<const W: usize> Not for Bitset<W> {
+    type Output = Self;
+
+    #[inline]
+    fn not(self) -> Self {
+        Self(self.0.map(|word| !word))
+    }
+}
+
+/// Serialized as the list of indexes.
+impl<const W: usize> Serialize for Bitset<W> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.count_ones() as usize))?;
+        for index in self.iter() {
+            seq.serialize_element(&index)?;
+        }
+        seq.end()
+    }
+}
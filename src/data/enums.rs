@@ -107,3 +107,14 @@ impl VeinType {
         )
     }
 }
+
+/// Ratio between a planet's rotation period and its orbital period.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Deserialize, Serialize)]
+pub enum Resonance {
+    /// 1:1, the planet is tidally locked.
+    TidalLock,
+    /// 1:2, the planet rotates twice per orbit.
+    Half,
+    /// 1:4, the planet rotates four times per orbit.
+    Quarter,
+}
@@ -1,8 +1,26 @@
+use super::star_graph::StarGraph;
 use super::star_planets::StarWithPlanets;
 use serde::Serialize;
+use std::cell::OnceCell;
 
 #[derive(Debug, Serialize)]
 pub struct Galaxy<'a> {
     pub seed: i32,
     pub stars: Vec<StarWithPlanets<'a>>,
+    #[serde(skip)]
+    graph: OnceCell<StarGraph>,
+}
+
+impl<'a> Galaxy<'a> {
+    pub fn new(seed: i32, stars: Vec<StarWithPlanets<'a>>) -> Self {
+        Self {
+            seed,
+            stars,
+            graph: OnceCell::new(),
+        }
+    }
+
+    pub fn get_graph(&self) -> &StarGraph {
+        self.graph.get_or_init(|| StarGraph::new(&self.stars))
+    }
 }
@@ -1,6 +1,32 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+/// Rules evaluate every star of a galaxy as one bit of a `StarMask`, which
+/// is sized for this many stars.
+pub const MAX_STAR_COUNT: usize = 1024;
+
+/// Whether stars have the hives of the Dark Fog update. Nothing else is gated
+/// on it, so `V0_9` is not a reproduction of builds before that update.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub enum GameVersion {
+    /// Stars have no hives. Everything else is generated like `V0_10`.
+    #[serde(rename = "0.9")]
+    V0_9,
+    /// The Dark Fog update and later.
+    #[default]
+    #[serde(rename = "0.10")]
+    V0_10,
+}
+
+impl GameVersion {
+    /// Whether `Star::new` draws the `hive_rand` stream.
+    pub fn has_hives(&self) -> bool {
+        *self >= GameVersion::V0_10
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GameDesc {
     #[serde(default = "GameDesc::default_star_count")]
@@ -13,6 +39,22 @@ pub struct GameDesc {
     pub hive_max_density: f64,
     #[serde(default)]
     pub use_actual_veins: bool,
+    /// Leaves out the hives with `V0_9`, the latest if omitted.
+    #[serde(default)]
+    pub version: GameVersion,
+}
+
+impl Default for GameDesc {
+    fn default() -> Self {
+        Self {
+            star_count: Self::default_star_count(),
+            resource_multiplier: Self::default_resource_multiplier(),
+            hive_initial_colonize: Self::default_hive_initial_colonize(),
+            hive_max_density: Self::default_hive_max_density(),
+            use_actual_veins: false,
+            version: GameVersion::default(),
+        }
+    }
 }
 
 impl GameDesc {
@@ -29,6 +71,31 @@ impl GameDesc {
         1.0
     }
 
+    /// Rejects settings the generator cannot handle.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.star_count == 0 || self.star_count > MAX_STAR_COUNT {
+            return Err(format!(
+                "starCount must be between 1 and {}, got {}",
+                MAX_STAR_COUNT, self.star_count
+            ));
+        }
+        if !self.resource_multiplier.is_finite() || self.resource_multiplier <= 0.0 {
+            return Err(format!(
+                "resourceMultiplier must be positive, got {}",
+                self.resource_multiplier
+            ));
+        }
+        for (name, value) in [
+            ("hiveInitialColonize", self.hive_initial_colonize),
+            ("hiveMaxDensity", self.hive_max_density),
+        ] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(format!("{} must not be negative, got {}", name, value));
+            }
+        }
+        Ok(())
+    }
+
     pub fn is_infinite_resource(&self) -> bool {
         self.resource_multiplier >= 99.5
     }
@@ -0,0 +1,69 @@
+use super::planet::Planet;
+use super::planet_raw_data::PlanetRawData;
+use super::vector_f3::VectorF3;
+
+/// Terrain of one planet sampled on an equirectangular grid.
+#[derive(Debug, Clone)]
+pub struct Heightmap {
+    pub width: usize,
+    pub height: usize,
+    /// Height above sea level in meters, row by row from north to south,
+    /// each row from longitude -180 to 180.
+    pub heights: Vec<f32>,
+}
+
+impl Heightmap {
+    /// Samples `width` x `width / 2` pixel centers. Gas giants have no terrain.
+    pub fn new(planet: &Planet, width: usize) -> Option<Self> {
+        if planet.is_gas_giant() {
+            return None;
+        }
+        let height = (width / 2).max(1);
+        let mut raw_data = PlanetRawData::new(planet);
+        let mut heights = Vec::with_capacity(width * height);
+        for row in 0..height {
+            let latitude = (90.0 - (row as f32 + 0.5) * 180.0 / height as f32).to_radians();
+            for col in 0..width {
+                let longitude = ((col as f32 + 0.5) * 360.0 / width as f32 - 180.0).to_radians();
+                let direction = VectorF3(
+                    latitude.cos() * longitude.sin(),
+                    latitude.sin(),
+                    -latitude.cos() * longitude.cos(),
+                );
+                heights.push(raw_data.query_height_normalized(&direction) - planet.radius);
+            }
+        }
+        Some(Self {
+            width,
+            height,
+            heights,
+        })
+    }
+
+    pub fn get_min(&self) -> f32 {
+        self.heights.iter().copied().fold(f32::INFINITY, f32::min)
+    }
+
+    pub fn get_max(&self) -> f32 {
+        self.heights
+            .iter()
+            .copied()
+            .fold(f32::NEG_INFINITY, f32::max)
+    }
+
+    /// Little-endian f32 heights in row order.
+    pub fn to_raw(&self) -> Vec<u8> {
+        self.heights.iter().flat_map(|h| h.to_le_bytes()).collect()
+    }
+
+    /// 16-bit grayscale big-endian samples stretched from the lowest to the
+    /// highest point.
+    pub fn to_gray16(&self) -> Vec<u8> {
+        let min = self.get_min();
+        let range = (self.get_max() - min).max(f32::EPSILON);
+        self.heights
+            .iter()
+            .flat_map(|h| (((h - min) / range * 65535.0).round() as u16).to_be_bytes())
+            .collect()
+    }
+}
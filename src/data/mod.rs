@@ -1,19 +1,24 @@
 pub mod birth_points;
+pub mod bitset;
 pub mod enums;
 pub mod galaxy;
 pub mod game_desc;
+pub mod heightmap;
 pub mod math;
 pub mod planet;
 pub mod planet_algorithms;
 pub mod planet_grid;
 pub mod planet_raw_data;
+pub mod planet_rule;
 pub mod pose;
 pub mod quaternion;
 pub mod random;
 pub mod random_table;
 pub mod rule;
+pub mod score;
 pub mod simplex_noise;
 pub mod star;
+pub mod star_graph;
 pub mod star_planets;
 pub mod theme_proto;
 pub mod vector2;
@@ -3,19 +3,22 @@ use crate::data::game_desc::GameDesc;
 use crate::data::planet_raw_data::PlanetRawData;
 use crate::data::vector_f2::VectorF2;
 
-use super::enums::{PlanetType, SpectrType, StarType, ThemeDistribute, VeinType};
+use super::enums::{PlanetType, Resonance, SpectrType, StarType, ThemeDistribute, VeinType};
 use super::pose::Pose;
 use super::quaternion::Quaternion;
 use super::random::DspRandom;
 use super::star::Star;
-use super::theme_proto::{ThemeProto, THEME_PROTOS};
+use super::theme_proto::{get_theme_protos, ThemeProto};
 use super::vector_f3::VectorF3;
-use super::vein::{ActualVein, EstimatedVein};
+use super::vein::{ActualVein, EstimatedVein, VeinGroup, VeinNode};
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 use std::cell::{Cell, OnceCell, RefCell};
 use std::f64::consts::PI;
 use std::rc::Rc;
 
+/// A sampled land fraction looks at one `PlanetGrid` vertex in this many.
+pub const LAND_SAMPLE_STEP: usize = 16;
+
 #[derive(Debug)]
 pub struct Planet<'a> {
     game_desc: &'a GameDesc,
@@ -54,6 +57,8 @@ pub struct Planet<'a> {
     gases: OnceCell<Vec<(i32, f32)>>,
     estimated_veins: OnceCell<Vec<EstimatedVein>>,
     actual_veins: OnceCell<Vec<ActualVein>>,
+    vein_groups: OnceCell<Vec<VeinGroup>>,
+    birth_point: OnceCell<VectorF3>,
     theme_algo_id: OnceCell<i32>,
 }
 
@@ -140,6 +145,8 @@ impl<'a> Planet<'a> {
             estimated_veins: OnceCell::new(),
             theme_algo_id: OnceCell::new(),
             actual_veins: OnceCell::new(),
+            vein_groups: OnceCell::new(),
+            birth_point: OnceCell::new(),
         }
     }
 
@@ -225,7 +232,7 @@ impl<'a> Planet<'a> {
         }
     }
 
-    fn get_luminosity(&self) -> f32 {
+    pub fn get_luminosity(&self) -> f32 {
         let mut luminosity =
             (self.star.get_light_balance_radius() / (self.get_sun_distance() + 0.01)).powf(0.6);
         if luminosity > 1.0 {
@@ -290,7 +297,23 @@ impl<'a> Planet<'a> {
         self.get_rotation_period() == self.get_orbital_period()
     }
 
-    fn get_orbit_inclination(&self) -> f32 {
+    /// The spin-orbit resonance the game rolled, which `get_rotation_period`
+    /// applies.
+    pub fn get_resonance(&self) -> Option<Resonance> {
+        if !self.get_eligible_for_resonance() {
+            None
+        } else if self.rotation_param > 0.96 {
+            Some(Resonance::TidalLock)
+        } else if self.rotation_param > 0.93 {
+            Some(Resonance::Half)
+        } else if self.rotation_param > 0.9 {
+            Some(Resonance::Quarter)
+        } else {
+            None
+        }
+    }
+
+    pub fn get_orbit_inclination(&self) -> f32 {
         let mut orbit_inclination = (self.orbit_inclination_factor * 16.0 - 8.0) as f32;
         if self.has_orbit_around() {
             orbit_inclination *= 2.2;
@@ -369,14 +392,11 @@ impl<'a> Planet<'a> {
 
     pub fn get_rotation_period(&self) -> f64 {
         *self.rotation_period.get_or_init(|| {
-            if self.get_eligible_for_resonance() {
-                if self.rotation_param > 0.96 {
-                    return self.get_orbital_period();
-                } else if self.rotation_param > 0.93 {
-                    return self.get_orbital_period() * 0.5;
-                } else if self.rotation_param > 0.9 {
-                    return self.get_orbital_period() * 0.25;
-                }
+            match self.get_resonance() {
+                Some(Resonance::TidalLock) => return self.get_orbital_period(),
+                Some(Resonance::Half) => return self.get_orbital_period() * 0.5,
+                Some(Resonance::Quarter) => return self.get_orbital_period() * 0.25,
+                None => {}
             }
             let gas_giant = self.is_gas_giant();
             let mut rotation_period = self.rotation_scale
@@ -407,7 +427,8 @@ impl<'a> Planet<'a> {
         self.theme.get_or_init(|| {
             let mut potential_themes: Vec<&'static ThemeProto> = Vec::new();
             let mut used_theme_ids = self.star.used_theme_ids.borrow_mut();
-            let unused_themes: Vec<&'static ThemeProto> = THEME_PROTOS
+            let theme_protos = get_theme_protos();
+            let unused_themes: Vec<&'static ThemeProto> = theme_protos
                 .iter()
                 .filter(|&theme| !used_theme_ids.contains(&theme.id))
                 .collect();
@@ -447,7 +468,7 @@ impl<'a> Planet<'a> {
                 }
             }
             if potential_themes.is_empty() {
-                for theme in &*THEME_PROTOS {
+                for theme in theme_protos {
                     if theme.planet_type == PlanetType::Desert {
                         potential_themes.push(theme);
                     }
@@ -856,291 +877,363 @@ impl<'a> Planet<'a> {
     }
 
     pub fn get_actual_veins(&self) -> &Vec<ActualVein> {
-        self.actual_veins.get_or_init(|| {
-            if self.gas_giant {
-                return Vec::with_capacity(0);
+        self.actual_veins.get_or_init(|| self.place_veins(None))
+    }
+
+    /// Every vein placed on the planet with the position and amount of its
+    /// nodes above ground. Places the veins again if `get_actual_veins` ran
+    /// first, which does not keep the nodes.
+    pub fn get_vein_groups(&self) -> &Vec<VeinGroup> {
+        self.vein_groups.get_or_init(|| {
+            let mut groups = vec![];
+            let veins = self.place_veins(Some(&mut groups));
+            let _ = self.actual_veins.set(veins);
+            groups
+        })
+    }
+
+    /// Places the veins like the game and sums the amount above ground of
+    /// each type. With `groups`, also collects every vein with its nodes.
+    fn place_veins(&self, mut groups: Option<&mut Vec<VeinGroup>>) -> Vec<ActualVein> {
+        if self.gas_giant {
+            return Vec::with_capacity(0);
+        }
+
+        let theme = self.get_theme();
+        let mut rand1 = DspRandom::new(self.seed);
+        rand1.next_f64();
+        rand1.next_f64();
+        rand1.next_f64();
+        rand1.next_f64();
+        let birth_seed = rand1.next_seed();
+        let mut rand2 = DspRandom::new(rand1.next_seed());
+        let mut vein_spots: Vec<i32> = (0..15_i32)
+            .map(|i| *theme.vein_spot.get((i - 1) as usize).unwrap_or(&0))
+            .collect();
+        let mut vein_counts: Vec<f32> = (0..15_i32)
+            .map(|i| *theme.vein_count.get((i - 1) as usize).unwrap_or(&0.0))
+            .collect();
+        let mut vein_opacities: Vec<f32> = (0..15_i32)
+            .map(|i| *theme.vein_opacity.get((i - 1) as usize).unwrap_or(&0.0))
+            .collect();
+
+        let mut random_vein_spots = |t: f64| {
+            for i in 0..11 {
+                if rand1.next_f64() >= t {
+                    return i;
+                }
             }
+            11
+        };
 
-            let theme = self.get_theme();
-            let mut rand1 = DspRandom::new(self.seed);
-            rand1.next_f64();
-            rand1.next_f64();
-            rand1.next_f64();
-            rand1.next_f64();
-            let birth_seed = rand1.next_seed();
-            let mut rand2 = DspRandom::new(rand1.next_seed());
-            let mut vein_spots: Vec<i32> = (0..15_i32)
-                .map(|i| *theme.vein_spot.get((i - 1) as usize).unwrap_or(&0))
-                .collect();
-            let mut vein_counts: Vec<f32> = (0..15_i32)
-                .map(|i| *theme.vein_count.get((i - 1) as usize).unwrap_or(&0.0))
-                .collect();
-            let mut vein_opacities: Vec<f32> = (0..15_i32)
-                .map(|i| *theme.vein_opacity.get((i - 1) as usize).unwrap_or(&0.0))
-                .collect();
+        let star_type_multiplier: f32 = match self.star.star_type {
+            StarType::MainSeqStar => match self.star.get_spectr() {
+                SpectrType::M => 2.5,
+                SpectrType::G => 0.7,
+                SpectrType::F => 0.6,
+                SpectrType::B => 0.4,
+                SpectrType::O => 1.6,
+                _ => 1.0,
+            },
+            StarType::GiantStar => 2.5,
+            StarType::WhiteDwarf => {
+                vein_spots[9] += 2 + random_vein_spots(0.45);
+                vein_counts[9] = 0.7;
+                vein_opacities[9] = 1.0;
+                vein_spots[10] += 2 + random_vein_spots(0.45);
+                vein_counts[10] = 0.7;
+                vein_opacities[10] = 1.0;
+                vein_spots[12] += 1 + random_vein_spots(0.5);
+                vein_counts[12] = 0.7;
+                vein_opacities[12] = 0.3;
+                3.5
+            }
+            StarType::NeutronStar => {
+                vein_spots[14] += 1 + random_vein_spots(0.65);
+                vein_counts[14] = 0.7;
+                vein_opacities[14] = 0.3;
+                4.5
+            }
+            StarType::BlackHole => {
+                vein_spots[14] += 1 + random_vein_spots(0.65);
+                vein_counts[14] = 0.7;
+                vein_opacities[14] = 0.3;
+                5.0
+            }
+        };
 
-            let mut random_vein_spots = |t: f64| {
-                for i in 0..11 {
-                    if rand1.next_f64() >= t {
-                        return i;
+        for (index1, rare_vein_ref) in theme.rare_veins.iter().enumerate() {
+            let rare_vein = *rare_vein_ref as usize;
+            let rare_vein_chance =
+                theme.rare_settings[index1 * 4 + (if self.star.is_birth() { 0 } else { 1 })];
+            let rare_setting_1 = theme.rare_settings[index1 * 4 + 2];
+            let rare_setting_2 = theme.rare_settings[index1 * 4 + 3];
+            let adjusted_rare_chance = 1.0 - (1.0 - rare_vein_chance).powf(star_type_multiplier);
+            let adjust_rare_count = 1.0 - (1.0 - rare_setting_2).powf(star_type_multiplier);
+            if rand1.next_f64() < (adjusted_rare_chance as f64) {
+                vein_spots[rare_vein] += 1;
+                vein_counts[rare_vein] = adjust_rare_count;
+                vein_opacities[rare_vein] = adjust_rare_count;
+                for _ in 1..12 {
+                    if rand1.next_f64() >= (rare_setting_1 as f64) {
+                        break;
                     }
+                    vein_spots[rare_vein] += 1;
                 }
-                11
-            };
+            }
+        }
 
-            let star_type_multiplier: f32 = match self.star.star_type {
-                StarType::MainSeqStar => match self.star.get_spectr() {
-                    SpectrType::M => 2.5,
-                    SpectrType::G => 0.7,
-                    SpectrType::F => 0.6,
-                    SpectrType::B => 0.4,
-                    SpectrType::O => 1.6,
-                    _ => 1.0,
-                },
-                StarType::GiantStar => 2.5,
-                StarType::WhiteDwarf => {
-                    vein_spots[9] += 2 + random_vein_spots(0.45);
-                    vein_counts[9] = 0.7;
-                    vein_opacities[9] = 1.0;
-                    vein_spots[10] += 2 + random_vein_spots(0.45);
-                    vein_counts[10] = 0.7;
-                    vein_opacities[10] = 1.0;
-                    vein_spots[12] += 1 + random_vein_spots(0.5);
-                    vein_counts[12] = 0.7;
-                    vein_opacities[12] = 0.3;
-                    3.5
-                }
-                StarType::NeutronStar => {
-                    vein_spots[14] += 1 + random_vein_spots(0.65);
-                    vein_counts[14] = 0.7;
-                    vein_opacities[14] = 0.3;
-                    4.5
-                }
-                StarType::BlackHole => {
-                    vein_spots[14] += 1 + random_vein_spots(0.65);
-                    vein_counts[14] = 0.7;
-                    vein_opacities[14] = 0.3;
-                    5.0
-                }
-            };
+        let is_rare_resource = self.game_desc.is_rare_resource();
+        let mut resource_coef = self.star.get_resource_coef();
+        let is_birth_planet = theme.distribute == ThemeDistribute::Birth;
+        if is_birth_planet {
+            resource_coef *= 2.0 / 3.0;
+        } else if is_rare_resource {
+            if resource_coef > 1.0 {
+                resource_coef = resource_coef.powf(0.8)
+            }
+            resource_coef *= 0.7;
+        }
+        let mut vein_vectors: Vec<(VeinType, VectorF3, bool)> = Vec::with_capacity(512);
+        // Fetch PlanetRawData once and thread it through all query_height calls
+        let mut raw_data = PlanetRawData::new(&self);
+
+        let birth_point = if is_birth_planet {
+            let star_direction = self.get_star_direction();
+            let birth_point_data =
+                BirthPoints::new(&mut raw_data, birth_seed, self.radius, star_direction);
+            vein_vectors.push((VeinType::Iron, birth_point_data.birth_resource_point0, true));
+            vein_vectors.push((
+                VeinType::Copper,
+                birth_point_data.birth_resource_point1,
+                true,
+            ));
+            let mut birth_point = birth_point_data.birth_point;
+            birth_point.normalize();
+            let _ = self.birth_point.set(birth_point);
+            birth_point * 0.75
+        } else {
+            let x = rand2.next_f64() * 2.0 - 1.0;
+            let y = rand2.next_f64() - 0.5;
+            let z = rand2.next_f64() * 2.0 - 1.0;
+            let mut birth_point = VectorF3::new(x as f32, y as f32, z as f32);
+            birth_point.normalize();
+            birth_point * (rand2.next_f64() * 0.4 + 0.2) as f32
+        };
 
-            for (index1, rare_vein_ref) in theme.rare_veins.iter().enumerate() {
-                let rare_vein = *rare_vein_ref as usize;
-                let rare_vein_chance =
-                    theme.rare_settings[index1 * 4 + (if self.star.is_birth() { 0 } else { 1 })];
-                let rare_setting_1 = theme.rare_settings[index1 * 4 + 2];
-                let rare_setting_2 = theme.rare_settings[index1 * 4 + 3];
-                let adjusted_rare_chance =
-                    1.0 - (1.0 - rare_vein_chance).powf(star_type_multiplier);
-                let adjust_rare_count = 1.0 - (1.0 - rare_setting_2).powf(star_type_multiplier);
-                if rand1.next_f64() < (adjusted_rare_chance as f64) {
-                    vein_spots[rare_vein] += 1;
-                    vein_counts[rare_vein] = adjust_rare_count;
-                    vein_opacities[rare_vein] = adjust_rare_count;
-                    for _ in 1..12 {
-                        if rand1.next_f64() >= (rare_setting_1 as f64) {
+        let is_infinite_resource = self.game_desc.is_infinite_resource();
+        // Fixed array indexed by VeinType discriminant (0..16) — avoids HashMap hashing overhead
+        let mut amount_map: [i32; 16] = [0; 16];
+
+        let min_vein_spacing = 2.1 / self.radius;
+        let min_vein_spacing_sq = (min_vein_spacing as f64) * (min_vein_spacing as f64);
+        let algo_id = self.get_algo_id();
+
+        for index3 in 1..15 {
+            if vein_vectors.len() >= 512 {
+                break;
+            }
+            let mut vein_spot_count = vein_spots[index3 as usize];
+            if vein_spot_count > 1 {
+                vein_spot_count += rand2.next_i32(3) - 1;
+            }
+            let vein_type: VeinType = unsafe { ::std::mem::transmute(index3) };
+            let min_sq_dist = min_vein_spacing_sq
+                * (if vein_type == VeinType::Oil {
+                    100_f64
+                } else {
+                    196_f64
+                });
+
+            for _ in 0..vein_spot_count {
+                for _ in 0..200 {
+                    let x = rand2.next_f64() * 2.0 - 1.0;
+                    let y = rand2.next_f64() * 2.0 - 1.0;
+                    let z = rand2.next_f64() * 2.0 - 1.0;
+                    let mut normal_dir = VectorF3(x as f32, y as f32, z as f32);
+                    if vein_type != VeinType::Oil {
+                        normal_dir += birth_point;
+                    }
+                    normal_dir.normalize();
+                    if self.can_place_vein(algo_id, &vein_type, &normal_dir, &mut raw_data) {
+                        let not_too_close_to_other_vein = vein_vectors.iter().all(|(_, pos, _)| {
+                            (pos.distance_sq_from(&normal_dir) as f64) >= min_sq_dist
+                        });
+                        if not_too_close_to_other_vein {
+                            vein_vectors.push((vein_type, normal_dir, false));
                             break;
                         }
-                        vein_spots[rare_vein] += 1;
                     }
                 }
+                if vein_vectors.len() >= 512 {
+                    break;
+                }
             }
+        }
 
-            let is_rare_resource = self.game_desc.is_rare_resource();
-            let mut resource_coef = self.star.get_resource_coef();
-            let is_birth_planet = theme.distribute == ThemeDistribute::Birth;
-            if is_birth_planet {
-                resource_coef *= 2.0 / 3.0;
-            } else if is_rare_resource {
-                if resource_coef > 1.0 {
-                    resource_coef = resource_coef.powf(0.8)
+        for (vein_type, vein_vector, is_birth_resource) in vein_vectors.iter() {
+            let is_oil = vein_type == &VeinType::Oil;
+            let normalized = vein_vector.normalized();
+            let rotation = Quaternion::from_to_rotation(&VectorF3::up(), &normalized);
+            let right_axis = &rotation * &VectorF3::right();
+            let forward_axis = &rotation * &VectorF3::forward();
+            let node_position = |offset: &VectorF2| {
+                let node_offset =
+                    ((right_axis * offset.0) + (forward_axis * offset.1)) * min_vein_spacing;
+                let pos = normalized + node_offset;
+                if is_oil {
+                    self.snap_to(&pos)
+                } else {
+                    pos
                 }
-                resource_coef *= 0.7;
-            }
-            let mut vein_vectors: Vec<(VeinType, VectorF3, bool)> = Vec::with_capacity(512);
-            // Fetch PlanetRawData once and thread it through all query_height calls
-            let mut raw_data = PlanetRawData::new(&self);
-
-            let birth_point = if is_birth_planet {
-                let star_direction = self.get_star_direction();
-                let birth_point_data =
-                    BirthPoints::new(&mut raw_data, birth_seed, self.radius, star_direction);
-                vein_vectors.push((VeinType::Iron, birth_point_data.birth_resource_point0, true));
-                vein_vectors.push((
-                    VeinType::Copper,
-                    birth_point_data.birth_resource_point1,
-                    true,
-                ));
-                let mut birth_point = birth_point_data.birth_point;
-                birth_point.normalize();
-                birth_point * 0.75
+            };
+            let vein_type_index = *vein_type as i32;
+            let target_node_count = if *is_birth_resource {
+                rand2.next_f64();
+                6
+            } else if is_oil {
+                rand2.next_f64();
+                1
             } else {
-                let x = rand2.next_f64() * 2.0 - 1.0;
-                let y = rand2.next_f64() - 0.5;
-                let z = rand2.next_f64() * 2.0 - 1.0;
-                let mut birth_point = VectorF3::new(x as f32, y as f32, z as f32);
-                birth_point.normalize();
-                birth_point * (rand2.next_f64() * 0.4 + 0.2) as f32
+                (vein_counts.get(vein_type_index as usize).unwrap()
+                    * (rand2.next_i32(5) + 20) as f32)
+                    .round_ties_even() as usize
             };
-
-            let is_infinite_resource = self.game_desc.is_infinite_resource();
-            // Fixed array indexed by VeinType discriminant (0..16) — avoids HashMap hashing overhead
-            let mut amount_map: [i32; 16] = [0; 16];
-
-            let min_vein_spacing = 2.1 / self.radius;
-            let min_vein_spacing_sq = (min_vein_spacing as f64) * (min_vein_spacing as f64);
-            let algo_id = self.get_algo_id();
-
-            for index3 in 1..15 {
-                if vein_vectors.len() >= 512 {
+            let mut vein_nodes = Vec::with_capacity(target_node_count);
+            vein_nodes.push(VectorF2::zero());
+            let vein_density = if *is_birth_resource {
+                0.2_f32
+            } else {
+                *vein_opacities.get(vein_type_index as usize).unwrap()
+            };
+            for _ in 0..20 {
+                if vein_nodes.len() >= target_node_count {
                     break;
                 }
-                let mut vein_spot_count = vein_spots[index3 as usize];
-                if vein_spot_count > 1 {
-                    vein_spot_count += rand2.next_i32(3) - 1;
-                }
-                let vein_type: VeinType = unsafe { ::std::mem::transmute(index3) };
-                let min_sq_dist = min_vein_spacing_sq
-                    * (if vein_type == VeinType::Oil {
-                        100_f64
-                    } else {
-                        196_f64
-                    });
-
-                for _ in 0..vein_spot_count {
-                    for _ in 0..200 {
-                        let x = rand2.next_f64() * 2.0 - 1.0;
-                        let y = rand2.next_f64() * 2.0 - 1.0;
-                        let z = rand2.next_f64() * 2.0 - 1.0;
-                        let mut normal_dir = VectorF3(x as f32, y as f32, z as f32);
-                        if vein_type != VeinType::Oil {
-                            normal_dir += birth_point;
+                for index8 in 0..vein_nodes.len() {
+                    let existing_node = vein_nodes.get(index8).unwrap();
+                    if existing_node.magnitude_sq() <= 36.0 {
+                        let random_angle_radians = rand2.next_f64() * PI * 2.0;
+                        let mut random_dir = VectorF2::new(
+                            random_angle_radians.cos() as f32,
+                            random_angle_radians.sin() as f32,
+                        );
+                        random_dir += existing_node * 0.2;
+                        random_dir.normalize();
+                        let new_node = existing_node + &random_dir;
+                        let not_too_close_to_other_node = vein_nodes
+                            .iter()
+                            .all(|v| v.distance_sq_from(&new_node) >= 0.85);
+                        if not_too_close_to_other_node {
+                            vein_nodes.push(new_node);
                         }
-                        normal_dir.normalize();
-                        if self.can_place_vein(algo_id, &vein_type, &normal_dir, &mut raw_data) {
-                            let not_too_close_to_other_vein =
-                                vein_vectors.iter().all(|(_, pos, _)| {
-                                    (pos.distance_sq_from(&normal_dir) as f64) >= min_sq_dist
-                                });
-                            if not_too_close_to_other_vein {
-                                vein_vectors.push((vein_type, normal_dir, false));
-                                break;
-                            }
+                        if vein_nodes.len() >= target_node_count {
+                            break;
                         }
                     }
-                    if vein_vectors.len() >= 512 {
-                        break;
-                    }
                 }
             }
-
-            for (vein_type, vein_vector, is_birth_resource) in vein_vectors.iter() {
-                let is_oil = vein_type == &VeinType::Oil;
-                let normalized = vein_vector.normalized();
-                let rotation = Quaternion::from_to_rotation(&VectorF3::up(), &normalized);
-                let right_axis = &rotation * &VectorF3::right();
-                let forward_axis = &rotation * &VectorF3::forward();
-                let vein_type_index = *vein_type as i32;
-                let target_node_count = if *is_birth_resource {
-                    rand2.next_f64();
-                    6
-                } else if is_oil {
-                    rand2.next_f64();
+            let adjusted_resource_coef = if is_oil {
+                resource_coef.powf(0.5)
+            } else {
+                resource_coef
+            };
+            let total_amount =
+                ((vein_density * 100000.0 * adjusted_resource_coef).round_ties_even() as i32)
+                    .max(20);
+            let amount_variance = if total_amount < 16000 {
+                ((total_amount as f32) * (15.0 / 16.0)) as i32
+            } else {
+                15000
+            };
+            let min_value = total_amount - amount_variance;
+            let value_range = amount_variance * 2 + 1;
+            let mut nodes = groups.is_some().then(Vec::new);
+            for node in vein_nodes.iter() {
+                let raw_amount = rand2.next_i32(value_range) + min_value;
+                let amount = if is_infinite_resource && !is_oil {
                     1
                 } else {
-                    (vein_counts.get(vein_type_index as usize).unwrap()
-                        * (rand2.next_i32(5) + 20) as f32)
-                        .round_ties_even() as usize
-                };
-                let mut vein_nodes = Vec::with_capacity(target_node_count);
-                vein_nodes.push(VectorF2::zero());
-                let vein_density = if *is_birth_resource {
-                    0.2_f32
-                } else {
-                    *vein_opacities.get(vein_type_index as usize).unwrap()
+                    let multiplier = if is_oil {
+                        self.game_desc.oil_amount_multiplier()
+                    } else {
+                        self.game_desc.resource_multiplier
+                    };
+                    (((raw_amount as f32) * 1.1 * multiplier).round_ties_even() as i32).max(1)
                 };
-                for _ in 0..20 {
-                    if vein_nodes.len() >= target_node_count {
-                        break;
+                if algo_id == 7 || theme.water_item_id == 0 {
+                    amount_map[*vein_type as usize] += amount;
+                    if let Some(nodes) = &mut nodes {
+                        nodes.push(VeinNode::new(node, node_position(node), amount));
                     }
-                    for index8 in 0..vein_nodes.len() {
-                        let existing_node = vein_nodes.get(index8).unwrap();
-                        if existing_node.magnitude_sq() <= 36.0 {
-                            let random_angle_radians = rand2.next_f64() * PI * 2.0;
-                            let mut random_dir = VectorF2::new(
-                                random_angle_radians.cos() as f32,
-                                random_angle_radians.sin() as f32,
-                            );
-                            random_dir += existing_node * 0.2;
-                            random_dir.normalize();
-                            let new_node = existing_node + &random_dir;
-                            let not_too_close_to_other_node = vein_nodes
-                                .iter()
-                                .all(|v| v.distance_sq_from(&new_node) >= 0.85);
-                            if not_too_close_to_other_node {
-                                vein_nodes.push(new_node);
-                            }
-                            if vein_nodes.len() >= target_node_count {
-                                break;
-                            }
-                        }
-                    }
-                }
-                let adjusted_resource_coef = if is_oil {
-                    resource_coef.powf(0.5)
-                } else {
-                    resource_coef
-                };
-                let total_amount = ((vein_density * 100000.0 * adjusted_resource_coef)
-                    .round_ties_even() as i32)
-                    .max(20);
-                let amount_variance = if total_amount < 16000 {
-                    ((total_amount as f32) * (15.0 / 16.0)) as i32
                 } else {
-                    15000
-                };
-                let min_value = total_amount - amount_variance;
-                let value_range = amount_variance * 2 + 1;
-                for pos in vein_nodes.iter() {
-                    let raw_amount = rand2.next_i32(value_range) + min_value;
-                    let amount = if is_infinite_resource && !is_oil {
-                        1
-                    } else {
-                        let multiplier = if is_oil {
-                            self.game_desc.oil_amount_multiplier()
-                        } else {
-                            self.game_desc.resource_multiplier
-                        };
-                        (((raw_amount as f32) * 1.1 * multiplier).round_ties_even() as i32).max(1)
-                    };
-                    if algo_id == 7 || theme.water_item_id == 0 {
+                    let pos = node_position(node);
+                    let surface_height = raw_data.query_height(&pos);
+                    if surface_height >= self.radius {
+                        // println!("{:?},{:?},{}", pos * surface_height, vein_type, amount);
                         amount_map[*vein_type as usize] += amount;
-                    } else {
-                        let node_offset =
-                            ((right_axis * pos.0) + (forward_axis * pos.1)) * min_vein_spacing;
-                        let mut pos = normalized + node_offset;
-                        if is_oil {
-                            pos = self.snap_to(&pos);
-                        }
-                        let surface_height = raw_data.query_height(&pos);
-                        if surface_height >= self.radius {
-                            // println!("{:?},{:?},{}", pos * surface_height, vein_type, amount);
-                            amount_map[*vein_type as usize] += amount;
+                        if let Some(nodes) = &mut nodes {
+                            nodes.push(VeinNode::new(node, pos, amount));
                         }
                     }
                 }
             }
+            if let (Some(groups), Some(nodes)) = (groups.as_deref_mut(), nodes) {
+                if !nodes.is_empty() {
+                    groups.push(VeinGroup::new(*vein_type, normalized, nodes));
+                }
+            }
+        }
 
-            amount_map
-                .iter()
-                .enumerate()
-                .filter(|(_, &amount)| amount > 0)
-                .map(|(i, &amount)| ActualVein {
-                    vein_type: unsafe { ::std::mem::transmute(i as i32) },
-                    amount,
-                })
-                .collect()
-        })
+        amount_map
+            .iter()
+            .enumerate()
+            .filter(|(_, &amount)| amount > 0)
+            .map(|(i, &amount)| ActualVein {
+                vein_type: unsafe { ::std::mem::transmute(i as i32) },
+                amount,
+            })
+            .collect()
+    }
+
+    /// Fraction of the surface within `max_latitude` degrees of the equator
+    /// that is at or above sea level. `exact` looks at every `PlanetGrid`
+    /// vertex, otherwise one in `LAND_SAMPLE_STEP`.
+    pub fn get_land_fraction(&self, exact: bool, max_latitude: f32) -> f32 {
+        if self.gas_giant {
+            return 0.0;
+        }
+        if self.get_theme().water_item_id == 0 {
+            return 1.0;
+        }
+        let step = if exact { 1 } else { LAND_SAMPLE_STEP };
+        PlanetRawData::new(self).land_fraction(self.radius, step, max_latitude)
+    }
+
+    /// Nodes of every placed vein of `vein_type`.
+    pub fn get_vein_nodes(&self, vein_type: &VeinType) -> Vec<&VeinNode> {
+        if !self.can_have_vein(vein_type) {
+            return vec![];
+        }
+        self.get_vein_groups()
+            .iter()
+            .filter(|group| &group.vein_type == vein_type)
+            .flat_map(|group| group.nodes.iter())
+            .collect()
+    }
+
+    /// Unit direction of the landing point, only on the birth planet.
+    pub fn get_birth_point(&self) -> Option<&VectorF3> {
+        if self.gas_giant || self.get_theme().distribute != ThemeDistribute::Birth {
+            return None;
+        }
+        self.get_actual_veins();
+        self.birth_point.get()
+    }
+
+    /// Converts an angle in degrees between two surface points to meters along the surface.
+    pub fn surface_distance(&self, angle: f32) -> f32 {
+        angle.to_radians() * self.radius
     }
 
     pub fn is_acutal_veins_generated(&self) -> bool {
@@ -46,26 +46,44 @@ pub trait PlanetAlgorithm {
     fn get_height(&self, index: usize) -> f64;
 }
 
+/// Number of planet algorithms, with IDs from 0.
+pub const ALGO_COUNT: usize = 15;
+
+type AlgoConstructor = fn(&Planet) -> Box<dyn PlanetAlgorithm>;
+
+/// Constructors of the algorithms by ID.
+const ALGO_CONSTRUCTORS: [AlgoConstructor; ALGO_COUNT] = [
+    |planet| Box::new(PlanetAlgorithm0::new(planet)),
+    |planet| Box::new(PlanetAlgorithm1::new(planet)),
+    |planet| Box::new(PlanetAlgorithm2::new(planet)),
+    |planet| Box::new(PlanetAlgorithm3::new(planet)),
+    |planet| Box::new(PlanetAlgorithm4::new(planet)),
+    |planet| Box::new(PlanetAlgorithm5::new(planet)),
+    |planet| Box::new(PlanetAlgorithm6::new(planet)),
+    |planet| Box::new(PlanetAlgorithm7::new(planet)),
+    |planet| Box::new(PlanetAlgorithm8::new(planet)),
+    |planet| Box::new(PlanetAlgorithm9::new(planet)),
+    |planet| Box::new(PlanetAlgorithm10::new(planet)),
+    |planet| Box::new(PlanetAlgorithm11::new(planet)),
+    |planet| Box::new(PlanetAlgorithm12::new(planet)),
+    |planet| Box::new(PlanetAlgorithm13::new(planet)),
+    |planet| Box::new(PlanetAlgorithm14::new(planet)),
+];
+
 /// Construct the algorithm matching the planet's algo ID.
 /// Returns a boxed, fully-initialized algorithm ready for lazy height queries.
 pub fn create_and_prepare_algo(planet: &Planet) -> Box<dyn PlanetAlgorithm> {
     let algo_id = planet.get_algo_id();
-    match algo_id {
-        0 => Box::new(PlanetAlgorithm0::new(planet)),
-        1 => Box::new(PlanetAlgorithm1::new(planet)),
-        2 => Box::new(PlanetAlgorithm2::new(planet)),
-        3 => Box::new(PlanetAlgorithm3::new(planet)),
-        4 => Box::new(PlanetAlgorithm4::new(planet)),
-        5 => Box::new(PlanetAlgorithm5::new(planet)),
-        6 => Box::new(PlanetAlgorithm6::new(planet)),
-        7 => Box::new(PlanetAlgorithm7::new(planet)),
-        8 => Box::new(PlanetAlgorithm8::new(planet)),
-        9 => Box::new(PlanetAlgorithm9::new(planet)),
-        10 => Box::new(PlanetAlgorithm10::new(planet)),
-        11 => Box::new(PlanetAlgorithm11::new(planet)),
-        12 => Box::new(PlanetAlgorithm12::new(planet)),
-        13 => Box::new(PlanetAlgorithm13::new(planet)),
-        14 => Box::new(PlanetAlgorithm14::new(planet)),
-        _ => panic!("Unknown planet algorithm ID: {}", algo_id),
+    let constructor = usize::try_from(algo_id)
+        .ok()
+        .and_then(|id| ALGO_CONSTRUCTORS.get(id));
+    match constructor {
+        Some(constructor) => constructor(planet),
+        None => panic!("Unknown planet algorithm ID: {}", algo_id),
     }
 }
+
+/// Whether `create_and_prepare_algo` can build the algorithm `algo_id`.
+pub fn is_known_algo(algo_id: i32) -> bool {
+    usize::try_from(algo_id).is_ok_and(|id| id < ALGO_COUNT)
+}
@@ -66,6 +66,28 @@ impl PlanetRawData {
         }
     }
 
+    /// Fraction of the `PlanetGrid` vertices within `max_latitude` degrees of
+    /// the equator that are at or above `sea_level`, looking at every `step`-th one.
+    pub fn land_fraction(&mut self, sea_level: f32, step: usize, max_latitude: f32) -> f32 {
+        let max_y = max_latitude.to_radians().sin();
+        let mut land = 0;
+        let mut total = 0;
+        for index in (0..DATA_LENGTH).step_by(step) {
+            if max_latitude < 90.0 && self.grid.get_vertex(index).1.abs() > max_y {
+                continue;
+            }
+            total += 1;
+            if self.get_height(index) * 0.01 >= sea_level {
+                land += 1;
+            }
+        }
+        if total == 0 {
+            0.0
+        } else {
+            land as f32 / total as f32
+        }
+    }
+
     #[inline]
     pub fn query_height(&mut self, vpos: &VectorF3) -> f32 {
         let mut vpos = *vpos;
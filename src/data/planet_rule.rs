@@ -0,0 +1,40 @@
+use super::bitset::PlanetMask;
+use super::planet::Planet;
+use super::rule::Evaluation;
+
+/// Indexed by planet instead of by star.
+pub type PlanetEvaluation = Evaluation<1>;
+
+/// Like `evaluate_safe!`, but over the planets of one star.
+#[macro_export]
+macro_rules! evaluate_planets {
+    ($planets:expr, $evaluation:expr, |$planet:ident| $logic:expr) => {{
+        let mut result = $crate::data::bitset::PlanetMask::new();
+        for (index, $planet) in $planets.iter().take($evaluation.get_len()).enumerate() {
+            if $evaluation.is_known(index) {
+                continue;
+            }
+            if $logic {
+                result.insert(index);
+            }
+        }
+        result
+    }};
+}
+
+#[allow(unused_variables)]
+pub trait PlanetRule {
+    fn get_priority(&self) -> i32 {
+        0
+    }
+
+    /// Whether the rule reads the planet theme (or anything derived from it).
+    /// Themes must be generated in star order, see `evaluate_unsafe!`.
+    fn needs_theme(&self) -> bool {
+        false
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        PlanetMask::new()
+    }
+}
@@ -1,4 +1,6 @@
+use super::bitset::{Bitset, StarMask, STAR_WORDS};
 use super::galaxy::Galaxy;
+use super::star_planets::StarWithPlanets;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
@@ -32,13 +34,13 @@ impl Condition {
 #[macro_export]
 macro_rules! evaluate_safe {
     ($galaxy:expr, $evaluation:expr, |$sp:ident| $logic:expr) => {{
-        let mut result: u64 = 0;
+        let mut result = $crate::data::bitset::StarMask::new();
         for (index, $sp) in $galaxy.stars.iter().take($evaluation.get_len()).enumerate() {
             if $evaluation.is_known(index) {
                 continue;
             }
             if $logic {
-                result |= 1 << index;
+                result.insert(index);
             }
         }
         result
@@ -48,7 +50,7 @@ macro_rules! evaluate_safe {
 #[macro_export]
 macro_rules! evaluate_unsafe {
     ($galaxy:expr, $evaluation:expr, |$sp:ident| $logic:expr) => {{
-        let mut result: u64 = 0;
+        let mut result = $crate::data::bitset::StarMask::new();
         for (index, $sp) in $galaxy.stars.iter().take($evaluation.get_len()).enumerate() {
             if $evaluation.is_known(index) {
                 if !$sp.is_safe() {
@@ -58,7 +60,7 @@ macro_rules! evaluate_unsafe {
             }
 
             if $logic {
-                result |= 1 << index;
+                result.insert(index);
             }
             $sp.mark_safe();
         }
@@ -66,36 +68,122 @@ macro_rules! evaluate_unsafe {
     }};
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarExplanation {
+    pub index: usize,
+    pub value: Option<f32>,
+    pub passed: bool,
+}
+
+/// Trace of one rule node: what it measured for each star it looked at,
+/// and the traces of its children in evaluation order.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Explanation {
+    pub rule: &'static str,
+    pub condition: Option<Condition>,
+    /// Galaxy-wide measurement, for nodes that compare one value per galaxy.
+    pub value: Option<f32>,
+    pub stars: Vec<StarExplanation>,
+    pub children: Vec<Explanation>,
+    pub result: StarMask,
+}
+
+impl Explanation {
+    pub fn new(rule: &'static str, result: StarMask) -> Self {
+        Self {
+            rule,
+            condition: None,
+            value: None,
+            stars: vec![],
+            children: vec![],
+            result,
+        }
+    }
+}
+
 #[allow(unused_variables)]
 pub trait Rule {
+    fn get_name(&self) -> &'static str;
+
     fn get_priority(&self) -> i32 {
         0
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
-        0
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        StarMask::new()
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        None
     }
+
+    /// The value the rule compares against its condition for one star, if any.
+    fn measure(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        None
+    }
+
+    /// Evaluates the rule like `evaluate`, but also records why each star passed or failed.
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        explain_with(self, galaxy, evaluation, |sp| self.measure(galaxy, sp))
+    }
+
+    /// Re-orders children from the profiling data gathered so far.
+    /// Only rule trees built by `profile::transform_rules_adaptive` do anything here.
+    fn adapt(&self) {}
+}
+
+/// The default `explain`, with the value of each star from `measure`. Rules
+/// that measure against galaxy-wide data compute it once and look each star
+/// up in it here.
+pub fn explain_with<R, F>(
+    rule: &R,
+    galaxy: &Galaxy,
+    evaluation: &Evaluation,
+    measure: F,
+) -> Explanation
+where
+    R: Rule + ?Sized,
+    F: Fn(&StarWithPlanets) -> Option<f32>,
+{
+    let result = rule.evaluate(galaxy, evaluation);
+    let mut explanation = Explanation::new(rule.get_name(), result);
+    explanation.condition = rule.get_condition().cloned();
+    for (index, sp) in galaxy.stars.iter().take(evaluation.get_len()).enumerate() {
+        if evaluation.is_known(index) {
+            continue;
+        }
+        explanation.stars.push(StarExplanation {
+            index,
+            value: measure(sp),
+            passed: result.contains(index),
+        });
+    }
+    explanation
 }
 
+/// Which stars, or planets of a star, are already decided while a rule tree
+/// is evaluated, and whether they passed.
 #[derive(Debug, Clone, Copy)]
-pub struct Evaluation {
-    value: u64,
-    unknown: u64,
+pub struct Evaluation<const W: usize = STAR_WORDS> {
+    value: Bitset<W>,
+    unknown: Bitset<W>,
     max_len: usize,
 }
 
-impl Evaluation {
+impl<const W: usize> Evaluation<W> {
     pub fn new(size: usize) -> Self {
         Self {
-            value: 0,
-            unknown: u64::MAX >> (u64::BITS - size as u32),
+            value: Bitset::new(),
+            unknown: Bitset::first(size),
             max_len: size,
         }
     }
 
     #[inline]
     pub fn is_known(&self, index: usize) -> bool {
-        (self.unknown & (1 << index)) == 0
+        !self.unknown.contains(index)
     }
 
     /// Returns the minium number of stars that still needs to be evaluated
@@ -107,33 +195,38 @@ impl Evaluation {
 
     #[inline]
     fn load_max_len(&mut self) {
-        self.max_len = (u64::BITS - self.unknown.leading_zeros()) as usize;
+        self.max_len = self.unknown.get_end();
     }
 
     #[inline]
-    pub fn accept_many(&mut self, indices: u64) {
+    pub fn accept_many(&mut self, indices: Bitset<W>) {
         self.value |= self.unknown & indices;
         self.unknown &= !indices;
         self.load_max_len();
     }
 
     #[inline]
-    pub fn reject_others(&mut self, indices: u64) {
+    pub fn reject_others(&mut self, indices: Bitset<W>) {
         self.value &= !self.unknown | indices;
         self.unknown &= indices;
         self.load_max_len();
     }
 
     #[inline]
-    pub fn collect_known(&self) -> u64 {
+    pub fn collect_known(&self) -> Bitset<W> {
         !self.unknown & self.value
     }
 
     #[inline]
-    pub fn collect_unknown(&self) -> u64 {
+    pub fn collect_unknown(&self) -> Bitset<W> {
         self.unknown | self.value
     }
 
+    #[inline]
+    pub fn get_unknown(&self) -> Bitset<W> {
+        self.unknown
+    }
+
     #[inline]
     pub fn is_done(&self) -> bool {
         self.max_len == 0
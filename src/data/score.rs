@@ -0,0 +1,7 @@
+use super::galaxy::Galaxy;
+
+/// Numeric counterpart of `Rule`: instead of a pass/fail bitmask, a score
+/// rates the whole galaxy so seeds can be ranked against each other.
+pub trait Score {
+    fn score(&self, galaxy: &Galaxy) -> f32;
+}
@@ -71,8 +71,12 @@ impl<'a> Star<'a> {
         let lifetime_factor = rand2.next_f64();
         let radius_exponent = rand2.next_f64() * 0.4 - 0.2;
         let radius_factor = 2_f64.powf(radius_exponent);
-        let hive_seed = rand2.next_seed();
-        let mut hive_rand = DspRandom::new(hive_seed);
+        // The last draw, so earlier versions without it generate the same otherwise.
+        let mut hive_rand = if game_desc.version.has_hives() {
+            DspRandom::new(rand2.next_seed())
+        } else {
+            DspRandom::new(0)
+        };
         let safety_factor_modifier = hive_rand.next_f64();
         let max_hive_count_modifier = hive_rand.next_i32(1000);
         let mass_params = (
@@ -200,7 +204,7 @@ impl<'a> Star<'a> {
         })
     }
 
-    fn get_lifetime(&self) -> f32 {
+    pub fn get_lifetime(&self) -> f32 {
         let unmodified_mass = self.get_unmodified_mass();
         let lifetime_exponent_base = if unmodified_mass < 2.0 {
             2.0 + 0.4 * (1.0 - (unmodified_mass as f64))
@@ -329,7 +333,7 @@ impl<'a> Star<'a> {
         })
     }
 
-    fn get_color(&self) -> f32 {
+    pub fn get_color(&self) -> f32 {
         match self.star_type {
             StarType::BlackHole | StarType::NeutronStar => 1.0,
             StarType::WhiteDwarf => 0.7,
@@ -481,6 +485,9 @@ impl<'a> Star<'a> {
 
     pub fn get_max_hive_count(&self) -> i32 {
         *self.max_hive_count.get_or_init(|| {
+            if !self.game_desc.version.has_hives() {
+                return 0;
+            }
             let star_type_hive_multiplier = match self.star_type {
                 StarType::BlackHole | StarType::NeutronStar => 2.0,
                 _ => 1.0,
@@ -495,7 +502,7 @@ impl<'a> Star<'a> {
     pub fn get_initial_hive_count(&self) -> i32 {
         *self.initial_hive_count.get_or_init(|| {
             let initial_colonize = self.game_desc.hive_initial_colonize;
-            if initial_colonize < 0.015 {
+            if initial_colonize < 0.015 || !self.game_desc.version.has_hives() {
                 return 0;
             }
             let max_hive_count = self.get_max_hive_count();
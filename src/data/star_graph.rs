@@ -0,0 +1,86 @@
+use super::bitset::StarMask;
+use super::star_planets::StarWithPlanets;
+use std::collections::VecDeque;
+
+/// Pairwise distances between the stars of a galaxy, in light years.
+#[derive(Debug)]
+pub struct StarGraph {
+    len: usize,
+    distances: Vec<f64>,
+    /// For each star, every other star sorted from nearest to farthest.
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl StarGraph {
+    pub fn new(stars: &[StarWithPlanets]) -> Self {
+        let len = stars.len();
+        let mut distances = vec![0.0; len * len];
+        for i in 0..len {
+            for j in (i + 1)..len {
+                let distance = stars[i]
+                    .star
+                    .position
+                    .distance_from(&stars[j].star.position);
+                distances[i * len + j] = distance;
+                distances[j * len + i] = distance;
+            }
+        }
+        let neighbors = (0..len)
+            .map(|i| {
+                let mut others: Vec<usize> = (0..len).filter(|&j| j != i).collect();
+                others.sort_by(|&a, &b| distances[i * len + a].total_cmp(&distances[i * len + b]));
+                others
+            })
+            .collect();
+        Self {
+            len,
+            distances,
+            neighbors,
+        }
+    }
+
+    pub fn distance(&self, a: usize, b: usize) -> f64 {
+        self.distances[a * self.len + b]
+    }
+
+    /// Distance to the `k`-th nearest other star, starting from `k = 1`.
+    pub fn nearest_distance(&self, index: usize, k: usize) -> Option<f64> {
+        let other = *self.neighbors[index].get(k.checked_sub(1)?)?;
+        Some(self.distance(index, other))
+    }
+
+    /// Number of other stars at most `radius` away.
+    pub fn count_within(&self, index: usize, radius: f64) -> usize {
+        self.neighbors[index].partition_point(|&other| self.distance(index, other) <= radius)
+    }
+
+    /// Distance to the nearest other star whose bit is set in `mask`.
+    pub fn nearest_in(&self, index: usize, mask: &StarMask) -> Option<f64> {
+        self.neighbors[index]
+            .iter()
+            .find(|&&other| mask.contains(other))
+            .map(|&other| self.distance(index, other))
+    }
+
+    /// Fewest hops from `from` to every star when no single hop may exceed
+    /// `max_hop`, or `None` for stars that cannot be reached that way.
+    pub fn hop_counts(&self, from: usize, max_hop: f64) -> Vec<Option<usize>> {
+        let mut hops = vec![None; self.len];
+        let mut queue = VecDeque::new();
+        hops[from] = Some(0);
+        queue.push_back(from);
+        while let Some(current) = queue.pop_front() {
+            let next_hops = hops[current].map(|h| h + 1);
+            for &other in &self.neighbors[current] {
+                if self.distance(current, other) > max_hop {
+                    break;
+                }
+                if hops[other].is_none() {
+                    hops[other] = next_hops;
+                    queue.push_back(other);
+                }
+            }
+        }
+        hops
+    }
+}
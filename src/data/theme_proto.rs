@@ -1,8 +1,10 @@
 use crate::data::vector2::Vector2;
 
 use super::enums::{PlanetType, ThemeDistribute, VeinType};
-use once_cell::sync::Lazy;
-use serde::Serialize;
+use super::planet_algorithms::is_known_algo;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -65,7 +67,209 @@ impl Default for ThemeProto {
     }
 }
 
-pub static THEME_PROTOS: Lazy<Vec<ThemeProto>> = Lazy::new(|| {
+static LOADED_THEME_PROTOS: OnceCell<Vec<ThemeProto>> = OnceCell::new();
+
+/// The table loaded with `load_theme_protos`, or the compiled one.
+pub fn get_theme_protos() -> &'static [ThemeProto] {
+    match LOADED_THEME_PROTOS.get() {
+        Some(loaded) => loaded,
+        None => &THEME_PROTOS,
+    }
+}
+
+/// Replaces the compiled table for the rest of the process. Fails if a table
+/// was already loaded, since galaxies generated before would not match the
+/// ones generated after.
+pub fn load_theme_protos(protos: Vec<ThemeProto>) -> Result<(), String> {
+    LOADED_THEME_PROTOS
+        .set(protos)
+        .map_err(|_| "Theme protos are already loaded".to_string())
+}
+
+/// Number of common vein types, `Iron` to `Oil`, that `VeinSpot`,
+/// `VeinCount` and `VeinOpacity` have an entry for.
+const COMMON_VEIN_COUNT: usize = VeinType::Oil as usize;
+
+const THEME_DISTRIBUTES: [ThemeDistribute; 4] = [
+    ThemeDistribute::Default,
+    ThemeDistribute::Birth,
+    ThemeDistribute::Interstellar,
+    ThemeDistribute::Rare,
+];
+
+const PLANET_TYPES: [PlanetType; 6] = [
+    PlanetType::None,
+    PlanetType::Volcano,
+    PlanetType::Ocean,
+    PlanetType::Desert,
+    PlanetType::Ice,
+    PlanetType::Gas,
+];
+
+/// Vein types a theme can list as rare, all but `None` and `Max`.
+const RARE_VEIN_TYPES: [VeinType; 14] = [
+    VeinType::Iron,
+    VeinType::Copper,
+    VeinType::Silicium,
+    VeinType::Titanium,
+    VeinType::Stone,
+    VeinType::Coal,
+    VeinType::Oil,
+    VeinType::Fireice,
+    VeinType::Diamond,
+    VeinType::Fractal,
+    VeinType::Crysrub,
+    VeinType::Grat,
+    VeinType::Bamboo,
+    VeinType::Mag,
+];
+
+/// `ThemeProtoSet.json` as dumped from the game.
+#[derive(Deserialize)]
+struct ThemeProtoSet {
+    #[serde(rename = "m_Structure")]
+    structure: ThemeProtoData,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThemeProtoData {
+    data_array: Vec<ThemeProtoEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ThemeProtoEntry {
+    #[serde(rename = "ID")]
+    id: i32,
+    name: String,
+    water_item_id: i32,
+    wind: f32,
+    distribute: i32,
+    temperature: f32,
+    planet_type: i32,
+    vein_spot: Vec<i32>,
+    vein_count: Vec<f32>,
+    vein_opacity: Vec<f32>,
+    rare_veins: Vec<i32>,
+    rare_settings: Vec<f32>,
+    gas_items: Vec<i32>,
+    gas_speeds: Vec<f32>,
+    algos: Vec<i32>,
+    mod_x: ThemeProtoVector,
+    mod_y: ThemeProtoVector,
+}
+
+#[derive(Deserialize)]
+struct ThemeProtoVector {
+    x: f64,
+    y: f64,
+}
+
+/// Reads the `ThemeProtoSet.json` that `generateThemeProto.js` takes, or only
+/// its `dataArray`, and checks that world generation can use them.
+pub fn parse_theme_protos(json: &str) -> Result<Vec<ThemeProto>, String> {
+    let entries: Vec<ThemeProtoEntry> = if json.trim_start().starts_with('[') {
+        serde_json::from_str(json).map_err(|e| e.to_string())?
+    } else {
+        serde_json::from_str::<ThemeProtoSet>(json)
+            .map_err(|e| e.to_string())?
+            .structure
+            .data_array
+    };
+    if entries.is_empty() {
+        return Err("No theme protos".to_string());
+    }
+    let mut ids = HashSet::new();
+    entries
+        .into_iter()
+        .map(|entry| {
+            if !ids.insert(entry.id) {
+                return Err(format!("Theme {} is defined twice", entry.id));
+            }
+            let id = entry.id;
+            entry
+                .into_theme_proto()
+                .map_err(|e| format!("Theme {}: {}", id, e))
+        })
+        .collect()
+}
+
+impl ThemeProtoEntry {
+    fn into_theme_proto(self) -> Result<ThemeProto, String> {
+        let vein_len = self.vein_spot.len();
+        if (vein_len != 0 && vein_len != COMMON_VEIN_COUNT)
+            || self.vein_count.len() != vein_len
+            || self.vein_opacity.len() != vein_len
+        {
+            return Err(format!(
+                "VeinSpot, VeinCount and VeinOpacity must all have 0 or {} entries, got {}, {} and {}",
+                COMMON_VEIN_COUNT,
+                vein_len,
+                self.vein_count.len(),
+                self.vein_opacity.len()
+            ));
+        }
+        if self.rare_settings.len() != self.rare_veins.len() * 4 {
+            return Err(format!(
+                "RareSettings must have 4 entries per rare vein, got {} for {} veins",
+                self.rare_settings.len(),
+                self.rare_veins.len()
+            ));
+        }
+        if self.gas_items.len() != self.gas_speeds.len() {
+            return Err(format!(
+                "GasItems and GasSpeeds must have the same length, got {} and {}",
+                self.gas_items.len(),
+                self.gas_speeds.len()
+            ));
+        }
+        if let Some(algo) = self.algos.iter().find(|&&algo| !is_known_algo(algo)) {
+            return Err(format!("Unknown algorithm {}", algo));
+        }
+        let distribute = usize::try_from(self.distribute)
+            .ok()
+            .and_then(|i| THEME_DISTRIBUTES.get(i))
+            .ok_or_else(|| format!("Unknown Distribute {}", self.distribute))?;
+        let planet_type = usize::try_from(self.planet_type)
+            .ok()
+            .and_then(|i| PLANET_TYPES.get(i))
+            .ok_or_else(|| format!("Unknown PlanetType {}", self.planet_type))?;
+        let rare_veins = self
+            .rare_veins
+            .iter()
+            .map(|&vein| {
+                RARE_VEIN_TYPES
+                    .into_iter()
+                    .find(|&rare| rare as i32 == vein)
+                    .ok_or_else(|| format!("Unknown rare vein {}", vein))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(ThemeProto {
+            id: self.id,
+            // Loaded once per process and kept like the compiled table.
+            name: Box::leak(self.name.into_boxed_str()),
+            wind: self.wind,
+            water_item_id: self.water_item_id,
+            distribute: *distribute,
+            temperature: self.temperature,
+            planet_type: *planet_type,
+            vein_spot: self.vein_spot,
+            vein_count: self.vein_count,
+            vein_opacity: self.vein_opacity,
+            rare_veins,
+            rare_settings: self.rare_settings,
+            gas_items: self.gas_items,
+            gas_speeds: self.gas_speeds,
+            algos: self.algos,
+            mod_x: Vector2(self.mod_x.x, self.mod_x.y),
+            mod_y: Vector2(self.mod_y.x, self.mod_y.y),
+        })
+    }
+}
+
+/// Dumped from the current game build by `generateThemeProto.js`.
+static THEME_PROTOS: Lazy<Vec<ThemeProto>> = Lazy::new(|| {
     vec![
         ThemeProto {
             id: 1,
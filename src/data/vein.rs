@@ -1,7 +1,9 @@
 use super::enums::VeinType;
+use super::vector_f2::VectorF2;
+use super::vector_f3::VectorF3;
 use serde::Serialize;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct EstimatedVein {
     pub vein_type: VeinType,
@@ -31,9 +33,17 @@ impl EstimatedVein {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Expected total amount, the midpoint of every range.
+    pub fn get_average_amount(&self) -> f32 {
+        ((self.min_patch + self.max_patch) as f32)
+            * ((self.min_group + self.max_group) as f32)
+            * ((self.min_amount + self.max_amount) as f32)
+            / 8.0
+    }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ActualVein {
     pub vein_type: VeinType,
@@ -48,3 +58,69 @@ impl Default for ActualVein {
         }
     }
 }
+
+/// Latitude and longitude in degrees of a direction from the planet center,
+/// north and east positive as shown in game.
+pub fn lat_long(direction: &VectorF3) -> (f32, f32) {
+    let direction = direction.normalized();
+    (
+        direction.1.clamp(-1.0, 1.0).asin().to_degrees(),
+        direction.0.atan2(-direction.2).to_degrees(),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VeinNode {
+    /// Position on the plane tangent to the group center, in node spacings.
+    pub offset: (f32, f32),
+    pub latitude: f32,
+    pub longitude: f32,
+    pub amount: i32, // times 4e-5 for oil
+    #[serde(skip)]
+    pub direction: VectorF3,
+}
+
+impl VeinNode {
+    pub fn new(offset: &VectorF2, direction: VectorF3, amount: i32) -> Self {
+        let direction = direction.normalized();
+        let (latitude, longitude) = lat_long(&direction);
+        Self {
+            offset: (offset.0, offset.1),
+            latitude,
+            longitude,
+            amount,
+            direction,
+        }
+    }
+
+    /// Angle in degrees between the node and a unit direction from the planet center.
+    pub fn angle_to(&self, direction: &VectorF3) -> f32 {
+        VectorF3::dot(&self.direction, direction)
+            .clamp(-1.0, 1.0)
+            .acos()
+            .to_degrees()
+    }
+}
+
+/// A vein as placed on the surface: its center and every node above ground.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VeinGroup {
+    pub vein_type: VeinType,
+    pub latitude: f32,
+    pub longitude: f32,
+    pub nodes: Vec<VeinNode>,
+}
+
+impl VeinGroup {
+    pub fn new(vein_type: VeinType, direction: VectorF3, nodes: Vec<VeinNode>) -> Self {
+        let (latitude, longitude) = lat_long(&direction);
+        Self {
+            vein_type,
+            latitude,
+            longitude,
+            nodes,
+        }
+    }
+}
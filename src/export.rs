@@ -0,0 +1,585 @@
+use crate::data::enums::{PlanetType, SpectrType, StarType, VeinType};
+use crate::data::galaxy::Galaxy;
+use crate::data::game_desc::GameDesc;
+use crate::data::rule::Rule;
+use crate::data::theme_proto::get_theme_protos;
+use crate::transform_rules::Rules;
+use crate::worldgen::galaxy_gen::{create_galaxy, find_stars};
+use arrow_array::types::Int8Type;
+use arrow_array::{
+    ArrayRef, DictionaryArray, Float32Array, Float64Array, Int32Array, Int8Array, RecordBatch,
+    StringArray, UInt8Array,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Error};
+use std::path::Path;
+use std::sync::Arc;
+
+/// Bumped whenever a column is added, removed or changes meaning.
+pub const EXPORT_VERSION: u32 = 1;
+
+/// Seeds per record batch. Each seed can produce thousands of vein rows.
+pub const EXPORT_CHUNK_SIZE: i32 = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportConfig {
+    pub game: GameDesc,
+    /// Only seeds where at least one star passes are exported.
+    #[serde(default)]
+    pub rule: Option<Rules>,
+}
+
+struct StarRow {
+    seed: i32,
+    index: u8,
+    name: String,
+    position: (f64, f64, f64),
+    mass: f32,
+    lifetime: f32,
+    age: f32,
+    temperature: f32,
+    star_type: StarType,
+    spectr: SpectrType,
+    luminosity: f32,
+    radius: f32,
+    dyson_radius: i32,
+    initial_hive_count: i32,
+    max_hive_count: i32,
+    color: f32,
+}
+
+struct PlanetRow {
+    seed: i32,
+    star_index: u8,
+    index: u8,
+    orbit_around: Option<u8>,
+    orbit_index: u8,
+    orbit_radius: f32,
+    orbit_inclination: f32,
+    orbit_longitude: f32,
+    orbital_period: f64,
+    obliquity: f32,
+    rotation_period: f64,
+    planet_type: PlanetType,
+    luminosity: f32,
+    theme_id: i32,
+    wind: f32,
+    water_item_id: i32,
+}
+
+struct GasRow {
+    seed: i32,
+    star_index: u8,
+    planet_index: u8,
+    item_id: i32,
+    rate: f32,
+}
+
+/// Estimated veins fill the min/max columns, actual veins fill `amount`.
+struct VeinRow {
+    seed: i32,
+    star_index: u8,
+    planet_index: u8,
+    vein_type: VeinType,
+    amount: Option<i32>,
+    min_group: Option<i32>,
+    max_group: Option<i32>,
+    min_patch: Option<i32>,
+    max_patch: Option<i32>,
+    min_amount: Option<i32>,
+    max_amount: Option<i32>,
+}
+
+#[derive(Default)]
+struct Rows {
+    stars: Vec<StarRow>,
+    planets: Vec<PlanetRow>,
+    gases: Vec<GasRow>,
+    veins: Vec<VeinRow>,
+}
+
+impl Rows {
+    fn append(&mut self, mut other: Rows) {
+        self.stars.append(&mut other.stars);
+        self.planets.append(&mut other.planets);
+        self.gases.append(&mut other.gases);
+        self.veins.append(&mut other.veins);
+    }
+
+    fn add_galaxy(&mut self, galaxy: &Galaxy, game: &GameDesc) {
+        let seed = galaxy.seed;
+        for sp in &galaxy.stars {
+            let star = &sp.star;
+            let star_index = star.index as u8;
+            self.stars.push(StarRow {
+                seed,
+                index: star_index,
+                name: sp.name.clone(),
+                position: (star.position.0, star.position.1, star.position.2),
+                mass: star.get_mass(),
+                lifetime: star.get_lifetime(),
+                age: star.get_age(),
+                temperature: star.get_temperature(),
+                star_type: star.star_type,
+                spectr: star.get_spectr(),
+                luminosity: star.get_luminosity(),
+                radius: star.get_radius(),
+                dyson_radius: star.get_dyson_radius(),
+                initial_hive_count: star.get_initial_hive_count(),
+                max_hive_count: star.get_max_hive_count(),
+                color: star.get_color(),
+            });
+            for planet in sp.get_planets() {
+                let planet_index = planet.index as u8;
+                let theme = planet.get_theme();
+                self.planets.push(PlanetRow {
+                    seed,
+                    star_index,
+                    index: planet_index,
+                    orbit_around: planet.orbit_around.borrow().map(|p| p.index as u8),
+                    orbit_index: planet.orbit_index as u8,
+                    orbit_radius: planet.get_orbital_radius(),
+                    orbit_inclination: planet.get_orbit_inclination(),
+                    orbit_longitude: planet.orbit_longitude,
+                    orbital_period: planet.get_orbital_period(),
+                    obliquity: planet.get_obliquity(),
+                    rotation_period: planet.get_rotation_period(),
+                    planet_type: *planet.get_type(),
+                    luminosity: planet.get_luminosity(),
+                    theme_id: theme.id,
+                    wind: theme.wind,
+                    water_item_id: theme.water_item_id,
+                });
+                for (item_id, rate) in planet.get_gases() {
+                    self.gases.push(GasRow {
+                        seed,
+                        star_index,
+                        planet_index,
+                        item_id: *item_id,
+                        rate: *rate,
+                    });
+                }
+                if game.use_actual_veins {
+                    for vein in planet.get_actual_veins() {
+                        self.veins.push(VeinRow {
+                            seed,
+                            star_index,
+                            planet_index,
+                            vein_type: vein.vein_type,
+                            amount: Some(vein.amount),
+                            min_group: None,
+                            max_group: None,
+                            min_patch: None,
+                            max_patch: None,
+                            min_amount: None,
+                            max_amount: None,
+                        });
+                    }
+                } else {
+                    for vein in planet.get_estimated_veins() {
+                        self.veins.push(VeinRow {
+                            seed,
+                            star_index,
+                            planet_index,
+                            vein_type: vein.vein_type,
+                            amount: None,
+                            min_group: Some(vein.min_group),
+                            max_group: Some(vein.max_group),
+                            min_patch: Some(vein.min_patch),
+                            max_patch: Some(vein.max_patch),
+                            min_amount: Some(vein.min_amount),
+                            max_amount: Some(vein.max_amount),
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+const STAR_TYPES: [StarType; 5] = [
+    StarType::MainSeqStar,
+    StarType::GiantStar,
+    StarType::WhiteDwarf,
+    StarType::NeutronStar,
+    StarType::BlackHole,
+];
+
+const SPECTR_TYPES: [SpectrType; 8] = [
+    SpectrType::M,
+    SpectrType::K,
+    SpectrType::G,
+    SpectrType::F,
+    SpectrType::A,
+    SpectrType::B,
+    SpectrType::O,
+    SpectrType::X,
+];
+
+const PLANET_TYPES: [PlanetType; 6] = [
+    PlanetType::None,
+    PlanetType::Volcano,
+    PlanetType::Ocean,
+    PlanetType::Desert,
+    PlanetType::Ice,
+    PlanetType::Gas,
+];
+
+const VEIN_TYPES: [VeinType; 15] = [
+    VeinType::None,
+    VeinType::Iron,
+    VeinType::Copper,
+    VeinType::Silicium,
+    VeinType::Titanium,
+    VeinType::Stone,
+    VeinType::Coal,
+    VeinType::Oil,
+    VeinType::Fireice,
+    VeinType::Diamond,
+    VeinType::Fractal,
+    VeinType::Crysrub,
+    VeinType::Grat,
+    VeinType::Bamboo,
+    VeinType::Mag,
+];
+
+/// IPC files allow only one dictionary per column, so every batch carries
+/// the full set of `names` rather than only the values it uses.
+fn dictionary<T: PartialEq>(
+    all: &[T],
+    names: impl Iterator<Item = String>,
+    values: impl Iterator<Item = T>,
+) -> ArrayRef {
+    let keys: Int8Array = values
+        .map(|value| all.iter().position(|a| *a == value).unwrap() as i8)
+        .collect();
+    let names = StringArray::from_iter_values(names);
+    Arc::new(DictionaryArray::<Int8Type>::try_new(keys, Arc::new(names)).unwrap())
+}
+
+fn enum_dictionary<T: PartialEq + Copy + std::fmt::Debug>(
+    all: &[T],
+    values: impl Iterator<Item = T>,
+) -> ArrayRef {
+    dictionary(all, all.iter().map(|a| format!("{:?}", a)), values)
+}
+
+fn theme_dictionary(theme_ids: impl Iterator<Item = i32>) -> ArrayRef {
+    let themes = get_theme_protos();
+    let ids: Vec<i32> = themes.iter().map(|theme| theme.id).collect();
+    let names = themes.iter().map(|theme| theme.name.to_owned());
+    dictionary(&ids, names, theme_ids)
+}
+
+fn dictionary_type() -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8))
+}
+
+fn schema(game: &GameDesc, table: &str, fields: Vec<Field>) -> SchemaRef {
+    let metadata = HashMap::from([
+        ("dsp_seed.table".to_owned(), table.to_owned()),
+        ("dsp_seed.version".to_owned(), EXPORT_VERSION.to_string()),
+        (
+            "dsp_seed.game".to_owned(),
+            serde_json::to_string(game).unwrap(),
+        ),
+    ]);
+    Arc::new(Schema::new_with_metadata(fields, metadata))
+}
+
+fn planet_location_fields() -> Vec<Field> {
+    vec![
+        Field::new("seed", DataType::Int32, false),
+        Field::new("starIndex", DataType::UInt8, false),
+        Field::new("planetIndex", DataType::UInt8, false),
+    ]
+}
+
+fn star_schema(game: &GameDesc) -> SchemaRef {
+    schema(
+        game,
+        "stars",
+        vec![
+            Field::new("seed", DataType::Int32, false),
+            Field::new("index", DataType::UInt8, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("x", DataType::Float64, false),
+            Field::new("y", DataType::Float64, false),
+            Field::new("z", DataType::Float64, false),
+            Field::new("mass", DataType::Float32, false),
+            Field::new("lifetime", DataType::Float32, false),
+            Field::new("age", DataType::Float32, false),
+            Field::new("temperature", DataType::Float32, false),
+            Field::new("type", dictionary_type(), false),
+            Field::new("spectr", dictionary_type(), false),
+            Field::new("luminosity", DataType::Float32, false),
+            Field::new("radius", DataType::Float32, false),
+            Field::new("dysonRadius", DataType::Int32, false),
+            Field::new("initialHiveCount", DataType::Int32, false),
+            Field::new("maxHiveCount", DataType::Int32, false),
+            Field::new("color", DataType::Float32, false),
+        ],
+    )
+}
+
+fn planet_schema(game: &GameDesc) -> SchemaRef {
+    schema(
+        game,
+        "planets",
+        vec![
+            Field::new("seed", DataType::Int32, false),
+            Field::new("starIndex", DataType::UInt8, false),
+            Field::new("index", DataType::UInt8, false),
+            Field::new("orbitAround", DataType::UInt8, true),
+            Field::new("orbitIndex", DataType::UInt8, false),
+            Field::new("orbitRadius", DataType::Float32, false),
+            Field::new("orbitInclination", DataType::Float32, false),
+            Field::new("orbitLongitude", DataType::Float32, false),
+            Field::new("orbitalPeriod", DataType::Float64, false),
+            Field::new("obliquity", DataType::Float32, false),
+            Field::new("rotationPeriod", DataType::Float64, false),
+            Field::new("type", dictionary_type(), false),
+            Field::new("luminosity", DataType::Float32, false),
+            Field::new("themeId", DataType::Int32, false),
+            Field::new("themeName", dictionary_type(), false),
+            Field::new("wind", DataType::Float32, false),
+            Field::new("waterItemId", DataType::Int32, false),
+        ],
+    )
+}
+
+fn gas_schema(game: &GameDesc) -> SchemaRef {
+    let mut fields = planet_location_fields();
+    fields.push(Field::new("itemId", DataType::Int32, false));
+    fields.push(Field::new("rate", DataType::Float32, false));
+    schema(game, "gases", fields)
+}
+
+fn vein_schema(game: &GameDesc) -> SchemaRef {
+    let mut fields = planet_location_fields();
+    fields.push(Field::new("type", dictionary_type(), false));
+    for name in [
+        "amount",
+        "minGroup",
+        "maxGroup",
+        "minPatch",
+        "maxPatch",
+        "minAmount",
+        "maxAmount",
+    ] {
+        fields.push(Field::new(name, DataType::Int32, true));
+    }
+    schema(game, "veins", fields)
+}
+
+fn star_batch(schema: &SchemaRef, rows: &[StarRow]) -> Result<RecordBatch, ArrowError> {
+    let f32_column = |f: fn(&StarRow) -> f32| -> ArrayRef {
+        Arc::new(Float32Array::from_iter_values(rows.iter().map(f)))
+    };
+    let i32_column = |f: fn(&StarRow) -> i32| -> ArrayRef {
+        Arc::new(Int32Array::from_iter_values(rows.iter().map(f)))
+    };
+    let f64_column = |f: fn(&StarRow) -> f64| -> ArrayRef {
+        Arc::new(Float64Array::from_iter_values(rows.iter().map(f)))
+    };
+    RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            i32_column(|r| r.seed),
+            Arc::new(UInt8Array::from_iter_values(rows.iter().map(|r| r.index))),
+            Arc::new(StringArray::from_iter_values(
+                rows.iter().map(|r| r.name.as_str()),
+            )),
+            f64_column(|r| r.position.0),
+            f64_column(|r| r.position.1),
+            f64_column(|r| r.position.2),
+            f32_column(|r| r.mass),
+            f32_column(|r| r.lifetime),
+            f32_column(|r| r.age),
+            f32_column(|r| r.temperature),
+            enum_dictionary(&STAR_TYPES, rows.iter().map(|r| r.star_type)),
+            enum_dictionary(&SPECTR_TYPES, rows.iter().map(|r| r.spectr)),
+            f32_column(|r| r.luminosity),
+            f32_column(|r| r.radius),
+            i32_column(|r| r.dyson_radius),
+            i32_column(|r| r.initial_hive_count),
+            i32_column(|r| r.max_hive_count),
+            f32_column(|r| r.color),
+        ],
+    )
+}
+
+fn planet_batch(schema: &SchemaRef, rows: &[PlanetRow]) -> Result<RecordBatch, ArrowError> {
+    let f32_column = |f: fn(&PlanetRow) -> f32| -> ArrayRef {
+        Arc::new(Float32Array::from_iter_values(rows.iter().map(f)))
+    };
+    let i32_column = |f: fn(&PlanetRow) -> i32| -> ArrayRef {
+        Arc::new(Int32Array::from_iter_values(rows.iter().map(f)))
+    };
+    let u8_column = |f: fn(&PlanetRow) -> u8| -> ArrayRef {
+        Arc::new(UInt8Array::from_iter_values(rows.iter().map(f)))
+    };
+    RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            i32_column(|r| r.seed),
+            u8_column(|r| r.star_index),
+            u8_column(|r| r.index),
+            Arc::new(rows.iter().map(|r| r.orbit_around).collect::<UInt8Array>()),
+            u8_column(|r| r.orbit_index),
+            f32_column(|r| r.orbit_radius),
+            f32_column(|r| r.orbit_inclination),
+            f32_column(|r| r.orbit_longitude),
+            Arc::new(Float64Array::from_iter_values(
+                rows.iter().map(|r| r.orbital_period),
+            )),
+            f32_column(|r| r.obliquity),
+            Arc::new(Float64Array::from_iter_values(
+                rows.iter().map(|r| r.rotation_period),
+            )),
+            enum_dictionary(&PLANET_TYPES, rows.iter().map(|r| r.planet_type)),
+            f32_column(|r| r.luminosity),
+            i32_column(|r| r.theme_id),
+            theme_dictionary(rows.iter().map(|r| r.theme_id)),
+            f32_column(|r| r.wind),
+            i32_column(|r| r.water_item_id),
+        ],
+    )
+}
+
+fn gas_batch(schema: &SchemaRef, rows: &[GasRow]) -> Result<RecordBatch, ArrowError> {
+    RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(Int32Array::from_iter_values(rows.iter().map(|r| r.seed))),
+            Arc::new(UInt8Array::from_iter_values(
+                rows.iter().map(|r| r.star_index),
+            )),
+            Arc::new(UInt8Array::from_iter_values(
+                rows.iter().map(|r| r.planet_index),
+            )),
+            Arc::new(Int32Array::from_iter_values(rows.iter().map(|r| r.item_id))),
+            Arc::new(Float32Array::from_iter_values(rows.iter().map(|r| r.rate))),
+        ],
+    )
+}
+
+fn vein_batch(schema: &SchemaRef, rows: &[VeinRow]) -> Result<RecordBatch, ArrowError> {
+    let optional_column = |f: fn(&VeinRow) -> Option<i32>| -> ArrayRef {
+        Arc::new(rows.iter().map(f).collect::<Int32Array>())
+    };
+    RecordBatch::try_new(
+        Arc::clone(schema),
+        vec![
+            Arc::new(Int32Array::from_iter_values(rows.iter().map(|r| r.seed))),
+            Arc::new(UInt8Array::from_iter_values(
+                rows.iter().map(|r| r.star_index),
+            )),
+            Arc::new(UInt8Array::from_iter_values(
+                rows.iter().map(|r| r.planet_index),
+            )),
+            enum_dictionary(&VEIN_TYPES, rows.iter().map(|r| r.vein_type)),
+            optional_column(|r| r.amount),
+            optional_column(|r| r.min_group),
+            optional_column(|r| r.max_group),
+            optional_column(|r| r.min_patch),
+            optional_column(|r| r.max_patch),
+            optional_column(|r| r.min_amount),
+            optional_column(|r| r.max_amount),
+        ],
+    )
+}
+
+fn arrow_error(e: ArrowError) -> Error {
+    Error::other(e)
+}
+
+struct TableWriter {
+    schema: SchemaRef,
+    writer: FileWriter<BufWriter<File>>,
+}
+
+impl TableWriter {
+    fn create(dir: &Path, schema: SchemaRef) -> Result<Self, Error> {
+        let table = &schema.metadata()["dsp_seed.table"];
+        let file = File::create(dir.join(format!("{}.arrow", table)))?;
+        let writer = FileWriter::try_new(BufWriter::new(file), &schema).map_err(arrow_error)?;
+        Ok(Self { schema, writer })
+    }
+
+    fn write(&mut self, batch: Result<RecordBatch, ArrowError>) -> Result<(), Error> {
+        let batch = batch.map_err(arrow_error)?;
+        if batch.num_rows() > 0 {
+            self.writer.write(&batch).map_err(arrow_error)?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), Error> {
+        self.writer.finish().map_err(arrow_error)
+    }
+}
+
+/// Writes `stars.arrow`, `planets.arrow`, `gases.arrow` and `veins.arrow`
+/// (Arrow IPC files, one record batch per chunk) into `dir`.
+/// `on_chunk` receives the end of each chunk and the number of seeds exported so far.
+pub fn export_range<F>(
+    pool: &ThreadPool,
+    game: &GameDesc,
+    rule: Option<&(dyn Rule + Send + Sync)>,
+    start: i32,
+    end: i32,
+    dir: &Path,
+    mut on_chunk: F,
+) -> Result<(), Error>
+where
+    F: FnMut(i32, usize),
+{
+    std::fs::create_dir_all(dir)?;
+    let mut stars = TableWriter::create(dir, star_schema(game))?;
+    let mut planets = TableWriter::create(dir, planet_schema(game))?;
+    let mut gases = TableWriter::create(dir, gas_schema(game))?;
+    let mut veins = TableWriter::create(dir, vein_schema(game))?;
+
+    let mut exported = 0;
+    let mut chunk_start = start;
+    while chunk_start < end {
+        let chunk_end = chunk_start.saturating_add(EXPORT_CHUNK_SIZE).min(end);
+        let galaxies: Vec<Rows> = pool.install(|| {
+            (chunk_start..chunk_end)
+                .into_par_iter()
+                .filter(|&seed| rule.is_none_or(|rule| !find_stars(seed, game, rule).is_empty()))
+                .map(|seed| {
+                    let habitable_count = Cell::new(0_i32);
+                    let galaxy = create_galaxy(seed, game, &habitable_count);
+                    let mut rows = Rows::default();
+                    rows.add_galaxy(&galaxy, game);
+                    rows
+                })
+                .collect()
+        });
+        exported += galaxies.len();
+        let mut rows = Rows::default();
+        for galaxy in galaxies {
+            rows.append(galaxy);
+        }
+        stars.write(star_batch(&stars.schema, &rows.stars))?;
+        planets.write(planet_batch(&planets.schema, &rows.planets))?;
+        gases.write(gas_batch(&gases.schema, &rows.gases))?;
+        veins.write(vein_batch(&veins.schema, &rows.veins))?;
+        on_chunk(chunk_end, exported);
+        chunk_start = chunk_end;
+    }
+
+    stars.finish()?;
+    planets.finish()?;
+    gases.finish()?;
+    veins.finish()
+}
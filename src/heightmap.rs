@@ -0,0 +1,88 @@
+use crate::data::game_desc::GameDesc;
+use crate::data::heightmap::Heightmap;
+use crate::data::planet::Planet;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+/// The galaxy settings of `heightmap --config`. Other keys, like the rule of
+/// a search config, are ignored.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeightmapConfig {
+    pub game: GameDesc,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeightmapFormat {
+    /// 16-bit grayscale PNG, stretched from the lowest to the highest point.
+    Png,
+    /// Little-endian f32 meters above sea level, without header.
+    Raw,
+}
+
+impl std::str::FromStr for HeightmapFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "png" => Ok(Self::Png),
+            "raw" => Ok(Self::Raw),
+            _ => Err(format!("Unknown heightmap format: {}", s)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeightmapReport {
+    pub seed: i32,
+    pub star: usize,
+    pub planet: usize,
+    pub theme: i32,
+    pub width: usize,
+    pub height: usize,
+    pub min_height: f32,
+    pub max_height: f32,
+    pub land_fraction: f32,
+    pub ocean_fraction: f32,
+}
+
+impl HeightmapReport {
+    pub fn new(seed: i32, star: usize, planet: &Planet, heightmap: &Heightmap) -> Self {
+        let land_fraction = planet.get_land_fraction(true, 90.0);
+        Self {
+            seed,
+            star,
+            planet: planet.index,
+            theme: planet.get_theme().id,
+            width: heightmap.width,
+            height: heightmap.height,
+            min_height: heightmap.get_min(),
+            max_height: heightmap.get_max(),
+            land_fraction,
+            ocean_fraction: 1.0 - land_fraction,
+        }
+    }
+}
+
+pub fn write_heightmap(
+    heightmap: &Heightmap,
+    format: HeightmapFormat,
+    path: &Path,
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        HeightmapFormat::Png => {
+            let mut encoder =
+                png::Encoder::new(&mut writer, heightmap.width as u32, heightmap.height as u32);
+            encoder.set_color(png::ColorType::Grayscale);
+            encoder.set_depth(png::BitDepth::Sixteen);
+            let mut png = encoder.write_header().map_err(Error::other)?;
+            png.write_image_data(&heightmap.to_gray16())
+                .map_err(Error::other)?;
+        }
+        HeightmapFormat::Raw => writer.write_all(&heightmap.to_raw())?,
+    }
+    writer.flush()
+}
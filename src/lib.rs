@@ -1,79 +1,133 @@
-#![cfg(target_arch = "wasm32")]
+//! World generator and rule engine of the DSP Seed Finder.
+//!
+//! Galaxies are generated exactly like the game does for a seed and
+//! [`GameDesc`]. [`create_galaxy`] returns an owned [`GalaxyView`] of every
+//! star and planet, and [`find_stars`] evaluates a rule tree, usually read
+//! from the same JSON the web UI sends, against the stars of a seed.
+//!
+//! ```
+//! use dsp_seed_finder::{create_galaxy, find_stars, transform_rules, GameDesc, Rules};
+//!
+//! let game: GameDesc = serde_json::from_str(r#"{ "starCount": 32 }"#).unwrap();
+//! let galaxy = create_galaxy(1, &game);
+//! assert_eq!(galaxy.stars.len(), 32);
+//!
+//! let rule: Rules = serde_json::from_str(
+//!     r#"{ "type": "Luminosity", "condition": { "type": "Gte", "value": 1.5 } }"#,
+//! )
+//! .unwrap();
+//! let rule = transform_rules(rule);
+//! for index in find_stars(1, &game, &rule) {
+//!     assert!(galaxy.stars[index].luminosity >= 1.5);
+//! }
+//! ```
 
+#[cfg(not(target_arch = "wasm32"))]
+mod checkpoint;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+#[cfg(not(target_arch = "wasm32"))]
+mod cluster;
+#[cfg(not(target_arch = "wasm32"))]
+mod control;
 mod data;
+#[cfg(not(target_arch = "wasm32"))]
+mod export;
+#[cfg(not(target_arch = "wasm32"))]
+mod heightmap;
+#[cfg(not(target_arch = "wasm32"))]
+mod parity;
+mod planet_rules;
+#[cfg(not(target_arch = "wasm32"))]
+mod profile;
 mod rules;
+#[cfg(not(target_arch = "wasm32"))]
+mod scheduler;
+mod scores;
+#[cfg(not(target_arch = "wasm32"))]
+mod search;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+#[cfg(not(target_arch = "wasm32"))]
+mod tests;
+mod transform_planet_rules;
 mod transform_rules;
+mod transform_scores;
+mod view;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
 mod worldgen;
 
-use data::game_desc::GameDesc;
-use serde::Serialize;
+use data::rule::Rule;
+use data::score::Score;
 use std::cell::Cell;
-use wasm_bindgen::prelude::*;
-use wasm_bindgen_futures::spawn_local;
-use worldgen::galaxy_gen::{create_galaxy, find_stars};
 
-#[wasm_bindgen]
-extern "C" {
-    #[wasm_bindgen(js_namespace = worldgen)]
-    async fn found(value: JsValue) -> JsValue;
+pub use data::enums::{PlanetType, SpectrType, StarType, VeinType};
+pub use data::game_desc::{GameDesc, GameVersion, MAX_STAR_COUNT};
+pub use data::vein::{ActualVein, EstimatedVein, VeinGroup, VeinNode};
+pub use transform_planet_rules::PlanetRules;
+pub use transform_rules::Rules;
+pub use transform_scores::Scores;
+pub use view::{GalaxyView, PlanetView, StarView, ThemeView, ViewOptions};
+
+/// A rule tree ready to be evaluated by [`find_stars`]. It can be shared
+/// between threads.
+pub struct CompiledRule(Box<dyn Rule + Send + Sync>);
+
+/// Compiles a rule tree, ordering the children of `And` and `Or` so that the
+/// cheapest rules run first.
+pub fn transform_rules(rule: Rules) -> CompiledRule {
+    CompiledRule(transform_rules::transform_rules(rule))
+}
+
+/// A score tree ready to be evaluated by [`score_galaxy`]. It can be shared
+/// between threads.
+pub struct CompiledScore(Box<dyn Score + Send + Sync>);
+
+/// Compiles a score tree, including the rules of its `Stars` filters.
+pub fn transform_scores(score: Scores) -> CompiledScore {
+    CompiledScore(transform_scores::transform_scores(score))
 }
 
-#[wasm_bindgen]
-#[allow(non_snake_case)]
-pub fn generate(seed: JsValue, gameDesc: JsValue) -> Result<JsValue, serde_wasm_bindgen::Error> {
-    let seed: i32 = serde_wasm_bindgen::from_value(seed)?;
-    let game_desc: GameDesc = serde_wasm_bindgen::from_value(gameDesc)?;
+/// Generates the galaxy of `seed` with all of its planets.
+///
+/// `game` should pass [`GameDesc::validate`].
+pub fn create_galaxy(seed: i32, game: &GameDesc) -> GalaxyView {
+    create_galaxy_with_options(seed, game, ViewOptions::default())
+}
+
+/// Like [`create_galaxy`], with the extra values `options` asks for.
+pub fn create_galaxy_with_options(seed: i32, game: &GameDesc, options: ViewOptions) -> GalaxyView {
     let habitable_count = Cell::new(0_i32);
-    let galaxy = create_galaxy(seed, &game_desc, &habitable_count);
-    galaxy.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+    let galaxy = worldgen::galaxy_gen::create_galaxy(seed, game, &habitable_count);
+    GalaxyView::new(&galaxy, options)
+}
+
+/// Indexes of the stars of `seed` that match `rule`, in ascending order.
+///
+/// Only generates what the rule looks at, so this is much faster than
+/// checking the result of [`create_galaxy`].
+pub fn find_stars(seed: i32, game: &GameDesc, rule: &CompiledRule) -> Vec<usize> {
+    worldgen::galaxy_gen::find_stars(seed, game, rule.0.as_ref())
+        .iter()
+        .collect()
+}
+
+/// The score of the galaxy of `seed`, as `rank` computes it for every seed.
+pub fn score_galaxy(seed: i32, game: &GameDesc, score: &CompiledScore) -> f32 {
+    worldgen::galaxy_gen::score_galaxy(seed, game, None, score.0.as_ref()).unwrap()
 }
 
-#[wasm_bindgen]
-#[allow(non_snake_case)]
-pub fn searchStar(
-    seed: JsValue,
-    gameDesc: JsValue,
-    rule: JsValue,
-) -> Result<JsValue, serde_wasm_bindgen::Error> {
-    let seed: i32 = serde_wasm_bindgen::from_value(seed)?;
-    let game_desc: GameDesc = serde_wasm_bindgen::from_value(gameDesc)?;
-    let rule = serde_wasm_bindgen::from_value(rule).unwrap();
-    let transformed = transform_rules::transform_rules(rule);
-    let star_indexes = find_stars(seed, &game_desc, &transformed);
-    let indexes: Vec<u8> = (0..64)
-        .filter(|&i| (star_indexes & (1_u64 << i)) != 0)
-        .collect();
-    serde_wasm_bindgen::to_value(&indexes)
+/// Replaces the compiled theme protos with `json`, the
+/// `ThemeProtoSet.json` dumped from the game or its `dataArray`. Call it before
+/// generating anything; it fails if the table is invalid or already loaded.
+pub fn load_theme_protos(json: &str) -> Result<(), String> {
+    let protos = data::theme_proto::parse_theme_protos(json)?;
+    data::theme_proto::load_theme_protos(protos)
 }
 
-#[wasm_bindgen]
-#[allow(non_snake_case)]
-pub fn findStars(gameDesc: JsValue, rule: JsValue, seeds: JsValue) {
-    spawn_local(async {
-        let game_desc: GameDesc = serde_wasm_bindgen::from_value(gameDesc).unwrap();
-        let mut seeds: Vec<i32> = serde_wasm_bindgen::from_value(seeds).unwrap();
-        let rule = serde_wasm_bindgen::from_value(rule).unwrap();
-        let transformed = transform_rules::transform_rules(rule);
-        loop {
-            let mut results: Vec<i32> = vec![];
-            for seed in seeds {
-                let star_indexes = find_stars(seed, &game_desc, &transformed);
-                if star_indexes != 0 {
-                    results.push(seed);
-                }
-            }
-            let result = serde_wasm_bindgen::to_value(&results).unwrap();
-            let next_batch: JsValue = found(result).await;
-            let next_seeds: Result<Vec<i32>, serde_wasm_bindgen::Error> =
-                serde_wasm_bindgen::from_value(next_batch);
-            match next_seeds {
-                Ok(f) => {
-                    seeds = f;
-                }
-                Err(_) => {
-                    break;
-                }
-            }
-        }
-    })
+/// Runs the `dsp_seed` program with its arguments, without the program name.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn run(args: &[String]) -> Result<(), std::io::Error> {
+    cli::run(args)
 }
@@ -0,0 +1,196 @@
+use crate::data::game_desc::GameDesc;
+use crate::worldgen::galaxy_gen::create_galaxy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+/// Relative tolerance of the numbers without one in `Tolerances::fields`.
+const DEFAULT_TOLERANCE: f64 = 1e-6;
+
+/// How far a number may be from the game's before it is a divergence. A
+/// tolerance `t` accepts `|a - b| <= t * max(1, |a|, |b|)`, so it is absolute
+/// below 1 and relative above. Two integers must be equal unless their field
+/// has a tolerance.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Tolerances {
+    pub default: f64,
+    /// By field name wherever it appears, e.g. `"orbitalPeriod"`. Values in
+    /// arrays use the name of the array.
+    pub fields: HashMap<String, f64>,
+}
+
+impl Default for Tolerances {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_TOLERANCE,
+            fields: HashMap::new(),
+        }
+    }
+}
+
+impl Tolerances {
+    fn get(&self, field: &str) -> f64 {
+        self.fields.get(field).copied().unwrap_or(self.default)
+    }
+}
+
+/// A galaxy exported from the game, in the JSON `create_galaxy` serializes
+/// to, with the settings it was generated with.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Fixture {
+    pub game: GameDesc,
+    pub galaxy: Value,
+    #[serde(default)]
+    pub tolerances: Tolerances,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Divergence {
+    /// Such as `stars[3].planets[1].theme.id`, or `stars[3].planets.length`
+    /// when the counts differ.
+    pub path: String,
+    /// Null when the dump does not have the field.
+    pub expected: Value,
+    pub actual: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParityReport {
+    pub file: String,
+    pub seed: i32,
+    /// The first field that does not match, none when the seed matches.
+    pub divergence: Option<Divergence>,
+}
+
+/// Generates the seed of `fixture` and compares every field that
+/// `create_galaxy` serializes. Fields only in the dump are ignored.
+pub fn check_fixture(fixture: &Fixture) -> Result<Option<Divergence>, String> {
+    fixture.game.validate()?;
+    let seed = fixture
+        .galaxy
+        .get("seed")
+        .and_then(Value::as_i64)
+        .and_then(|seed| i32::try_from(seed).ok())
+        .ok_or("The galaxy has no valid seed")?;
+    let habitable_count = Cell::new(0_i32);
+    let galaxy = create_galaxy(seed, &fixture.game, &habitable_count);
+    let actual = serde_json::to_value(&galaxy).map_err(|e| e.to_string())?;
+    Ok(first_divergence(
+        &fixture.galaxy,
+        &actual,
+        &fixture.tolerances,
+    ))
+}
+
+/// Checks every `.json` fixture in `dir`, in file name order.
+pub fn check_fixtures(dir: &Path) -> Result<Vec<ParityReport>, Error> {
+    let mut paths = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    paths
+        .into_iter()
+        .map(|path| {
+            let file = path.display().to_string();
+            let invalid =
+                |e: String| Error::new(ErrorKind::InvalidData, format!("{}: {}", file, e));
+            let fixture: Fixture =
+                serde_json::from_slice(&fs::read(&path)?).map_err(|e| invalid(e.to_string()))?;
+            let divergence = check_fixture(&fixture).map_err(invalid)?;
+            Ok(ParityReport {
+                seed: fixture.galaxy["seed"].as_i64().unwrap_or_default() as i32,
+                file,
+                divergence,
+            })
+        })
+        .collect()
+}
+
+/// The first value of `actual` that is missing from `expected` or differs
+/// beyond its tolerance. Within an object the plain fields are compared
+/// before the nested ones, so a star's own values come before its planets.
+pub fn first_divergence(
+    expected: &Value,
+    actual: &Value,
+    tolerances: &Tolerances,
+) -> Option<Divergence> {
+    compare(expected, actual, tolerances, String::new(), "")
+}
+
+fn compare(
+    expected: &Value,
+    actual: &Value,
+    tolerances: &Tolerances,
+    path: String,
+    field: &str,
+) -> Option<Divergence> {
+    let diverge = |expected: Value, actual: Value| {
+        Some(Divergence {
+            path: path.clone(),
+            expected,
+            actual,
+        })
+    };
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let is_nested = |value: &Value| value.is_object() || value.is_array();
+            let (nested, plain): (Vec<_>, Vec<_>) =
+                actual.iter().partition(|(_, value)| is_nested(value));
+            plain.into_iter().chain(nested).find_map(|(key, value)| {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", path, key)
+                };
+                compare(
+                    expected.get(key).unwrap_or(&Value::Null),
+                    value,
+                    tolerances,
+                    path,
+                    key,
+                )
+            })
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if expected.len() != actual.len() {
+                return Some(Divergence {
+                    path: format!("{}.length", path),
+                    expected: expected.len().into(),
+                    actual: actual.len().into(),
+                });
+            }
+            expected
+                .iter()
+                .zip(actual)
+                .enumerate()
+                .find_map(|(i, (expected, actual))| {
+                    let path = format!("{}[{}]", path, i);
+                    compare(expected, actual, tolerances, path, field)
+                })
+        }
+        (Value::Number(a), Value::Number(b)) => {
+            let matches = if a.is_f64() || b.is_f64() || tolerances.fields.contains_key(field) {
+                let (a, b) = (a.as_f64().unwrap(), b.as_f64().unwrap());
+                (a - b).abs() <= tolerances.get(field) * a.abs().max(b.abs()).max(1.0)
+            } else {
+                a == b
+            };
+            if matches {
+                None
+            } else {
+                diverge(a.clone().into(), b.clone().into())
+            }
+        }
+        (expected, actual) if expected == actual => None,
+        (expected, actual) => diverge(expected.clone(), actual.clone()),
+    }
+}
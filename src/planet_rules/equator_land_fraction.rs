@@ -0,0 +1,39 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::data::rule::Condition;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+/// Like `LandFraction`, but only within `latitude` degrees north or south of
+/// the equator.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleEquatorLandFraction {
+    pub latitude: f32,
+    #[serde(default)]
+    pub exact: bool,
+    pub condition: Condition,
+}
+
+impl PlanetRule for PlanetRuleEquatorLandFraction {
+    fn get_priority(&self) -> i32 {
+        if self.exact {
+            104
+        } else {
+            103
+        }
+    }
+
+    fn needs_theme(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            self.condition
+                .eval(planet.get_land_fraction(self.exact, self.latitude))
+        })
+    }
+}
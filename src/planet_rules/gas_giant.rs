@@ -0,0 +1,17 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::evaluate_planets;
+
+pub struct PlanetRuleGasGiant;
+
+impl PlanetRule for PlanetRuleGasGiant {
+    fn get_priority(&self) -> i32 {
+        32
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| planet.is_gas_giant())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::data::rule::Condition;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleGasRate {
+    pub gas_type: i32,
+    pub condition: Condition,
+}
+
+impl PlanetRule for PlanetRuleGasRate {
+    fn get_priority(&self) -> i32 {
+        50
+    }
+
+    fn needs_theme(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            let rate: f32 = planet
+                .get_gases()
+                .iter()
+                .filter(|(gas_type, _)| *gas_type == self.gas_type)
+                .map(|(_, rate)| *rate)
+                .sum();
+            self.condition.eval(rate)
+        })
+    }
+}
@@ -0,0 +1,15 @@
+pub mod and;
+pub mod equator_land_fraction;
+pub mod gas_giant;
+pub mod gas_rate;
+pub mod land_fraction;
+pub mod obliquity;
+pub mod ocean_type;
+pub mod or;
+pub mod orbital_period;
+pub mod resonance;
+pub mod rotation_period;
+pub mod satellite;
+pub mod theme_id;
+pub mod tidal_lock;
+pub mod vein_amount;
@@ -0,0 +1,26 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::data::rule::Condition;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+/// Axial tilt in degrees, regardless of direction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleObliquity {
+    pub condition: Condition,
+}
+
+impl PlanetRule for PlanetRuleObliquity {
+    fn get_priority(&self) -> i32 {
+        35
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            self.condition.eval(planet.get_obliquity().abs())
+        })
+    }
+}
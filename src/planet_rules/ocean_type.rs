@@ -0,0 +1,28 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleOceanType {
+    pub ocean_type: i32,
+}
+
+impl PlanetRule for PlanetRuleOceanType {
+    fn get_priority(&self) -> i32 {
+        42
+    }
+
+    fn needs_theme(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            planet.get_theme().water_item_id == self.ocean_type
+        })
+    }
+}
@@ -0,0 +1,34 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+
+pub struct PlanetRuleOr {
+    pub rules: Vec<Box<dyn PlanetRule + Send + Sync>>,
+}
+
+impl PlanetRule for PlanetRuleOr {
+    fn get_priority(&self) -> i32 {
+        self.rules
+            .iter()
+            .map(|rule| rule.get_priority())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn needs_theme(&self) -> bool {
+        self.rules.iter().any(|rule| rule.needs_theme())
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        let mut e = *evaluation;
+        for rule in &self.rules {
+            let result = rule.evaluate(planets, &e);
+            e.accept_many(result);
+            if e.is_done() {
+                return e.collect_known();
+            }
+        }
+        e.collect_known()
+    }
+}
@@ -0,0 +1,26 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::data::rule::Condition;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+/// Orbital period in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleOrbitalPeriod {
+    pub condition: Condition,
+}
+
+impl PlanetRule for PlanetRuleOrbitalPeriod {
+    fn get_priority(&self) -> i32 {
+        35
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            self.condition.eval(planet.get_orbital_period() as f32)
+        })
+    }
+}
@@ -0,0 +1,27 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::enums::Resonance;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleResonance {
+    pub resonances: Vec<Resonance>,
+}
+
+impl PlanetRule for PlanetRuleResonance {
+    fn get_priority(&self) -> i32 {
+        33
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            planet
+                .get_resonance()
+                .is_some_and(|resonance| self.resonances.contains(&resonance))
+        })
+    }
+}
@@ -0,0 +1,17 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::evaluate_planets;
+
+pub struct PlanetRuleSatellite;
+
+impl PlanetRule for PlanetRuleSatellite {
+    fn get_priority(&self) -> i32 {
+        31
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| planet.has_orbit_around())
+    }
+}
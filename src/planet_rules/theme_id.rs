@@ -0,0 +1,28 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleThemeId {
+    pub theme_ids: Vec<i32>,
+}
+
+impl PlanetRule for PlanetRuleThemeId {
+    fn get_priority(&self) -> i32 {
+        40
+    }
+
+    fn needs_theme(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            self.theme_ids.contains(&planet.get_theme().id)
+        })
+    }
+}
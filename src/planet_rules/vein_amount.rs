@@ -0,0 +1,66 @@
+use crate::data::bitset::PlanetMask;
+use crate::data::enums::{StarType, VeinType};
+use crate::data::planet::Planet;
+use crate::data::planet_rule::PlanetEvaluation;
+use crate::data::planet_rule::PlanetRule;
+use crate::data::rule::Condition;
+use crate::evaluate_planets;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetRuleVeinAmount {
+    pub use_actual: bool,
+    pub vein: VeinType,
+    pub condition: Condition,
+}
+
+impl PlanetRuleVeinAmount {
+    /// Same estimate as `StarWithPlanets::get_avg_vein`, for a single planet.
+    fn get_value(&self, planet: &Planet) -> f32 {
+        if self.vein == VeinType::Mag
+            && planet.star.star_type != StarType::BlackHole
+            && planet.star.star_type != StarType::NeutronStar
+        {
+            return 0.0;
+        }
+        if !planet.can_have_vein(&self.vein) {
+            return 0.0;
+        }
+        if self.use_actual || planet.is_acutal_veins_generated() {
+            planet
+                .get_actual_veins()
+                .iter()
+                .filter(|vein| vein.vein_type == self.vein)
+                .map(|vein| vein.amount as f32)
+                .sum()
+        } else {
+            planet
+                .get_estimated_veins()
+                .iter()
+                .filter(|vein| vein.vein_type == self.vein)
+                .map(|vein| vein.get_average_amount())
+                .sum()
+        }
+    }
+}
+
+impl PlanetRule for PlanetRuleVeinAmount {
+    fn get_priority(&self) -> i32 {
+        if self.use_actual {
+            101
+        } else {
+            51
+        }
+    }
+
+    fn needs_theme(&self) -> bool {
+        true
+    }
+
+    fn evaluate(&self, planets: &[Planet], evaluation: &PlanetEvaluation) -> PlanetMask {
+        evaluate_planets!(planets, evaluation, |planet| {
+            self.condition.eval(self.get_value(planet))
+        })
+    }
+}
@@ -0,0 +1,363 @@
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::{Condition, Evaluation, Explanation, Rule};
+use crate::data::star_planets::StarWithPlanets;
+use crate::rules;
+use crate::transform_rules::{transform_rules, Rules};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+/// Children with fewer calls than this keep their static position.
+const MIN_CALLS: u64 = 100;
+
+#[derive(Debug, Default)]
+pub struct RuleStats {
+    calls: AtomicU64,
+    nanos: AtomicU64,
+    checked: AtomicU64,
+    passed: AtomicU64,
+    /// Calls where any star passed.
+    hits: AtomicU64,
+}
+
+impl RuleStats {
+    fn record(&self, nanos: u64, checked: u32, passed: u32) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.checked.fetch_add(checked as u64, Ordering::Relaxed);
+        self.passed.fetch_add(passed as u64, Ordering::Relaxed);
+    }
+
+    fn record_hit(&self) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    /// Average time spent per star the rule was asked about.
+    pub fn nanos_per_star(&self) -> f64 {
+        self.nanos.load(Ordering::Relaxed) as f64
+            / self.checked.load(Ordering::Relaxed).max(1) as f64
+    }
+
+    /// Fraction of the stars the rule was asked about that passed.
+    pub fn pass_rate(&self) -> f64 {
+        self.passed.load(Ordering::Relaxed) as f64
+            / self.checked.load(Ordering::Relaxed).max(1) as f64
+    }
+
+    /// Fraction of the calls where any star passed.
+    pub fn hit_rate(&self) -> f64 {
+        self.hits.load(Ordering::Relaxed) as f64 / self.calls().max(1) as f64
+    }
+
+    /// Expected cost of the rule as a child of `And` per star it removes,
+    /// or as a child of `Or` per star it accepts. As a child of
+    /// `CompositeAnd` or `CompositeOr`, whose children decide the whole
+    /// galaxy, the cost per call is divided by the share of calls that
+    /// decide it instead. Lower runs first. None until the rule has been
+    /// called `MIN_CALLS` times.
+    fn rank(&self, reject: bool, composite: bool) -> Option<f64> {
+        let calls = self.calls();
+        if calls < MIN_CALLS {
+            return None;
+        }
+        let (cost, rate) = if composite {
+            let nanos = self.nanos.load(Ordering::Relaxed) as f64 / calls as f64;
+            (nanos, self.hit_rate())
+        } else {
+            (self.nanos_per_star(), self.pass_rate())
+        };
+        let useful = if reject { 1.0 - rate } else { rate };
+        Some(cost / useful.max(f64::EPSILON))
+    }
+}
+
+/// Times every evaluation of the wrapped rule.
+pub struct ProfiledRule {
+    pub stats: Arc<RuleStats>,
+    rule: Box<dyn Rule + Send + Sync>,
+}
+
+impl Rule for ProfiledRule {
+    fn get_name(&self) -> &'static str {
+        self.rule.get_name()
+    }
+
+    fn get_priority(&self) -> i32 {
+        self.rule.get_priority()
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        let start = Instant::now();
+        let result = self.rule.evaluate(galaxy, evaluation);
+        let unknown = evaluation.get_unknown();
+        self.stats.record(
+            start.elapsed().as_nanos() as u64,
+            unknown.count_ones(),
+            (result & unknown).count_ones(),
+        );
+        if !result.is_empty() {
+            self.stats.record_hit();
+        }
+        result
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        self.rule.get_condition()
+    }
+
+    fn measure(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        self.rule.measure(galaxy, sp)
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        self.rule.explain(galaxy, evaluation)
+    }
+
+    fn adapt(&self) {
+        self.rule.adapt()
+    }
+}
+
+/// `And` or `Or`, or with `composite` `CompositeAnd` or `CompositeOr`,
+/// whose children run in the order measured to be cheapest.
+pub struct RuleAdaptive {
+    reject: bool,
+    composite: bool,
+    rules: Vec<ProfiledRule>,
+    order: RwLock<Vec<usize>>,
+}
+
+impl RuleAdaptive {
+    fn get_order(&self) -> Vec<usize> {
+        self.order.read().unwrap().clone()
+    }
+
+    fn apply(&self, e: &mut Evaluation, result: StarMask) {
+        if self.reject {
+            e.reject_others(result);
+        } else {
+            e.accept_many(result);
+        }
+    }
+
+    fn collect(&self, e: &Evaluation) -> StarMask {
+        if self.reject {
+            e.collect_unknown()
+        } else {
+            e.collect_known()
+        }
+    }
+
+    /// Result of a composite: the first star alone when the galaxy passes.
+    fn decide(&self, passed: bool) -> StarMask {
+        if passed {
+            StarMask::first(1)
+        } else {
+            StarMask::new()
+        }
+    }
+}
+
+impl Rule for RuleAdaptive {
+    fn get_name(&self) -> &'static str {
+        match (self.composite, self.reject) {
+            (false, true) => "And",
+            (false, false) => "Or",
+            (true, true) => "CompositeAnd",
+            (true, false) => "CompositeOr",
+        }
+    }
+
+    fn get_priority(&self) -> i32 {
+        self.rules
+            .iter()
+            .map(|rule| rule.get_priority())
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        if self.composite {
+            // Every child sees every star, the first one that decides the
+            // galaxy ends the evaluation.
+            for index in self.get_order() {
+                let result = self.rules[index].evaluate(galaxy, evaluation);
+                if result.is_empty() == self.reject {
+                    return self.decide(!self.reject);
+                }
+            }
+            return self.decide(self.reject);
+        }
+        let mut e = *evaluation;
+        for index in self.get_order() {
+            let result = self.rules[index].evaluate(galaxy, &e);
+            self.apply(&mut e, result);
+            if e.is_done() {
+                break;
+            }
+        }
+        self.collect(&e)
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        if self.composite {
+            let mut children = vec![];
+            let mut result = self.decide(self.reject);
+            for index in self.get_order() {
+                let child = self.rules[index].explain(galaxy, evaluation);
+                let decided = child.result.is_empty() == self.reject;
+                children.push(child);
+                if decided {
+                    result = self.decide(!self.reject);
+                    break;
+                }
+            }
+            let mut explanation = Explanation::new(self.get_name(), result);
+            explanation.children = children;
+            return explanation;
+        }
+        let mut e = *evaluation;
+        let mut children = vec![];
+        for index in self.get_order() {
+            let child = self.rules[index].explain(galaxy, &e);
+            self.apply(&mut e, child.result);
+            children.push(child);
+            if e.is_done() {
+                break;
+            }
+        }
+        let mut explanation = Explanation::new(self.get_name(), self.collect(&e));
+        explanation.children = children;
+        explanation
+    }
+
+    fn adapt(&self) {
+        let ranks: Vec<Option<f64>> = self
+            .rules
+            .iter()
+            .map(|rule| rule.stats.rank(self.reject, self.composite))
+            .collect();
+        // Unmeasured children keep their position, which is their static
+        // priority order, and the measured ones are sorted into the rest.
+        let mut order = self.get_order();
+        let mut measured: Vec<usize> = order
+            .iter()
+            .copied()
+            .filter(|&index| ranks[index].is_some())
+            .collect();
+        measured.sort_by(|&a, &b| ranks[a].unwrap().total_cmp(&ranks[b].unwrap()));
+        let mut measured = measured.into_iter();
+        for index in order.iter_mut() {
+            if ranks[*index].is_some() {
+                *index = measured.next().unwrap();
+            }
+        }
+        *self.order.write().unwrap() = order;
+        for rule in &self.rules {
+            rule.adapt();
+        }
+    }
+}
+
+/// One profiled node, listed in the pre-order of the original rule tree.
+pub struct ProfileEntry {
+    pub depth: usize,
+    pub name: &'static str,
+    pub stats: Arc<RuleStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileReport {
+    pub depth: usize,
+    pub rule: &'static str,
+    pub calls: u64,
+    pub nanos_per_star: f64,
+    pub pass_rate: f64,
+}
+
+pub fn report(entries: &[ProfileEntry]) -> Vec<ProfileReport> {
+    entries
+        .iter()
+        .map(|entry| ProfileReport {
+            depth: entry.depth,
+            rule: entry.name,
+            calls: entry.stats.calls(),
+            nanos_per_star: entry.stats.nanos_per_star(),
+            pass_rate: entry.stats.pass_rate(),
+        })
+        .collect()
+}
+
+fn adaptive_children(
+    rules: Vec<Rules>,
+    entries: &mut Vec<ProfileEntry>,
+    depth: usize,
+) -> Vec<ProfiledRule> {
+    let mut children: Vec<ProfiledRule> = rules
+        .into_iter()
+        .map(|rule| {
+            let stats = Arc::new(RuleStats::default());
+            let index = entries.len();
+            entries.push(ProfileEntry {
+                depth,
+                name: "",
+                stats: Arc::clone(&stats),
+            });
+            let rule = transform_rules_adaptive(rule, entries, depth + 1);
+            entries[index].name = rule.get_name();
+            ProfiledRule { stats, rule }
+        })
+        .collect();
+    children.sort_by_key(|rule| rule.get_priority());
+    children
+}
+
+fn adaptive(
+    reject: bool,
+    composite: bool,
+    rules: Vec<Rules>,
+    entries: &mut Vec<ProfileEntry>,
+    depth: usize,
+) -> Box<dyn Rule + Send + Sync> {
+    let rules = adaptive_children(rules, entries, depth);
+    let order = (0..rules.len()).collect();
+    Box::new(RuleAdaptive {
+        reject,
+        composite,
+        rules,
+        order: RwLock::new(order),
+    })
+}
+
+/// Like `transform_rules`, but every child of an `And`, `Or`, `CompositeAnd`
+/// or `CompositeOr` is profiled and `Rule::adapt` re-orders them by measured
+/// cost and selectivity.
+/// Profiled children are appended to `entries`.
+pub fn transform_rules_adaptive(
+    r: Rules,
+    entries: &mut Vec<ProfileEntry>,
+    depth: usize,
+) -> Box<dyn Rule + Send + Sync> {
+    match r {
+        Rules::And { rules } => adaptive(true, false, rules, entries, depth),
+        Rules::Or { rules } => adaptive(false, false, rules, entries, depth),
+        Rules::Composite { rule, condition } => Box::new(rules::composite::RuleComposite {
+            rule: transform_rules_adaptive(*rule, entries, depth),
+            condition,
+        }),
+        Rules::CompositeAnd { rules } => adaptive(true, true, rules, entries, depth),
+        Rules::CompositeOr { rules } => adaptive(false, true, rules, entries, depth),
+        Rules::Nearest { rule, condition } => Box::new(rules::nearest::RuleNearest {
+            rule: transform_rules_adaptive(*rule, entries, depth),
+            condition,
+        }),
+        other => transform_rules(other),
+    }
+}
@@ -0,0 +1,49 @@
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Explanation;
+use crate::data::rule::Rule;
+use crate::data::score::Score;
+use crate::evaluate_safe;
+
+/// Compares one galaxy-wide value with `condition`. Every star passes when it
+/// holds and none do otherwise, so it can gate an `And` on the whole seed.
+pub struct RuleAggregate {
+    pub score: Box<dyn Score + Send + Sync>,
+    pub condition: Condition,
+}
+
+impl Rule for RuleAggregate {
+    fn get_name(&self) -> &'static str {
+        "Aggregate"
+    }
+
+    fn get_priority(&self) -> i32 {
+        110
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        if !self.condition.eval(self.score.score(galaxy)) {
+            return StarMask::new();
+        }
+        evaluate_safe!(galaxy, evaluation, |_sp| true)
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let value = self.score.score(galaxy);
+        let result = if self.condition.eval(value) {
+            evaluate_safe!(galaxy, evaluation, |_sp| true)
+        } else {
+            StarMask::new()
+        };
+        let mut explanation = Explanation::new(self.get_name(), result);
+        explanation.condition = Some(self.condition.clone());
+        explanation.value = Some(value);
+        explanation
+    }
+}
@@ -1,5 +1,7 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Evaluation;
+use crate::data::rule::Explanation;
 use crate::data::rule::Rule;
 
 pub struct RuleAnd {
@@ -7,6 +9,10 @@ pub struct RuleAnd {
 }
 
 impl Rule for RuleAnd {
+    fn get_name(&self) -> &'static str {
+        "And"
+    }
+
     fn get_priority(&self) -> i32 {
         self.rules
             .iter()
@@ -15,7 +21,7 @@ impl Rule for RuleAnd {
             .unwrap_or_default()
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         let mut e = *evaluation;
         for rule in &self.rules {
             let result = rule.evaluate(galaxy, &e);
@@ -26,4 +32,26 @@ impl Rule for RuleAnd {
         }
         e.collect_unknown()
     }
+
+    fn adapt(&self) {
+        for rule in &self.rules {
+            rule.adapt();
+        }
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let mut e = *evaluation;
+        let mut children = vec![];
+        for rule in &self.rules {
+            let child = rule.explain(galaxy, &e);
+            e.reject_others(child.result);
+            children.push(child);
+            if e.is_done() {
+                break;
+            }
+        }
+        let mut explanation = Explanation::new(self.get_name(), e.collect_unknown());
+        explanation.children = children;
+        explanation
+    }
 }
@@ -1,8 +1,10 @@
+use crate::data::bitset::StarMask;
 use crate::data::enums::VeinType;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_unsafe;
 use serde::{Deserialize, Serialize};
 
@@ -14,7 +16,21 @@ pub struct RuleAverageVeinAmount {
     pub condition: Condition,
 }
 
+impl RuleAverageVeinAmount {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        if self.use_actual {
+            sp.get_actual_vein(&self.vein)
+        } else {
+            sp.get_avg_vein(&self.vein)
+        }
+    }
+}
+
 impl Rule for RuleAverageVeinAmount {
+    fn get_name(&self) -> &'static str {
+        "AverageVeinAmount"
+    }
+
     fn get_priority(&self) -> i32 {
         if self.use_actual {
             101
@@ -23,14 +39,17 @@ impl Rule for RuleAverageVeinAmount {
         }
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_unsafe!(galaxy, evaluation, |sp| {
-            let count = if self.use_actual {
-                sp.get_actual_vein(&self.vein)
-            } else {
-                sp.get_avg_vein(&self.vein)
-            };
-            self.condition.eval(count)
+            self.condition.eval(self.get_value(sp))
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
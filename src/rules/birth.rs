@@ -1,3 +1,4 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
@@ -8,10 +9,14 @@ use serde::{Deserialize, Serialize};
 pub struct RuleBirth {}
 
 impl Rule for RuleBirth {
+    fn get_name(&self) -> &'static str {
+        "Birth"
+    }
+
     fn get_priority(&self) -> i32 {
         10
     }
-    fn evaluate(&self, _: &Galaxy, _: &Evaluation) -> u64 {
-        1
+    fn evaluate(&self, _: &Galaxy, _: &Evaluation) -> StarMask {
+        StarMask::first(1)
     }
 }
@@ -1,6 +1,8 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
+use crate::data::rule::Explanation;
 use crate::data::rule::Rule;
 
 pub struct RuleComposite {
@@ -9,18 +11,45 @@ pub struct RuleComposite {
 }
 
 impl Rule for RuleComposite {
+    fn get_name(&self) -> &'static str {
+        "Composite"
+    }
+
     fn get_priority(&self) -> i32 {
         self.rule.get_priority()
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         let result = self.rule.evaluate(galaxy, evaluation);
         if self.condition.eval(result.count_ones() as f32) {
-            1
+            StarMask::first(1)
         } else {
-            0
+            StarMask::new()
         }
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn adapt(&self) {
+        self.rule.adapt();
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let child = self.rule.explain(galaxy, evaluation);
+        let count = child.result.count_ones() as f32;
+        let result = if self.condition.eval(count) {
+            StarMask::first(1)
+        } else {
+            StarMask::new()
+        };
+        let mut explanation = Explanation::new(self.get_name(), result);
+        explanation.condition = Some(self.condition.clone());
+        explanation.value = Some(count);
+        explanation.children = vec![child];
+        explanation
+    }
 }
 
 pub struct RuleCompositeAnd {
@@ -28,6 +57,10 @@ pub struct RuleCompositeAnd {
 }
 
 impl Rule for RuleCompositeAnd {
+    fn get_name(&self) -> &'static str {
+        "CompositeAnd"
+    }
+
     fn get_priority(&self) -> i32 {
         self.rules
             .iter()
@@ -36,14 +69,37 @@ impl Rule for RuleCompositeAnd {
             .unwrap_or_default()
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         for rule in &self.rules {
             let result = rule.evaluate(galaxy, evaluation);
-            if result == 0 {
-                return 0;
+            if result.is_empty() {
+                return StarMask::new();
             }
         }
-        1
+        StarMask::first(1)
+    }
+
+    fn adapt(&self) {
+        for rule in &self.rules {
+            rule.adapt();
+        }
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let mut children = vec![];
+        let mut result = StarMask::first(1);
+        for rule in &self.rules {
+            let child = rule.explain(galaxy, evaluation);
+            let failed = child.result.is_empty();
+            children.push(child);
+            if failed {
+                result = StarMask::new();
+                break;
+            }
+        }
+        let mut explanation = Explanation::new(self.get_name(), result);
+        explanation.children = children;
+        explanation
     }
 }
 
@@ -52,6 +108,10 @@ pub struct RuleCompositeOr {
 }
 
 impl Rule for RuleCompositeOr {
+    fn get_name(&self) -> &'static str {
+        "CompositeOr"
+    }
+
     fn get_priority(&self) -> i32 {
         self.rules
             .iter()
@@ -60,13 +120,36 @@ impl Rule for RuleCompositeOr {
             .unwrap_or_default()
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         for rule in &self.rules {
             let result = rule.evaluate(galaxy, evaluation);
-            if result != 0 {
-                return 1;
+            if !result.is_empty() {
+                return StarMask::first(1);
+            }
+        }
+        StarMask::new()
+    }
+
+    fn adapt(&self) {
+        for rule in &self.rules {
+            rule.adapt();
+        }
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let mut children = vec![];
+        let mut result = StarMask::new();
+        for rule in &self.rules {
+            let child = rule.explain(galaxy, evaluation);
+            let passed = !child.result.is_empty();
+            children.push(child);
+            if passed {
+                result = StarMask::first(1);
+                break;
             }
         }
-        0
+        let mut explanation = Explanation::new(self.get_name(), result);
+        explanation.children = children;
+        explanation
     }
 }
@@ -1,7 +1,9 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_safe;
 use crate::evaluate_unsafe;
 use serde::{Deserialize, Serialize};
@@ -14,7 +16,32 @@ pub struct RuleGasCount {
     pub condition: Condition,
 }
 
+impl RuleGasCount {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        let planets = sp.get_planets();
+        let targets = if let Some(ice) = self.ice {
+            planets
+                .iter()
+                .filter(|planet| {
+                    let theme = planet.get_theme();
+                    planet.is_gas_giant() && (theme.temperature < 0.0) == ice
+                })
+                .count()
+        } else {
+            planets
+                .iter()
+                .filter(|planet| planet.is_gas_giant())
+                .count()
+        };
+        targets as f32
+    }
+}
+
 impl Rule for RuleGasCount {
+    fn get_name(&self) -> &'static str {
+        "GasCount"
+    }
+
     fn get_priority(&self) -> i32 {
         if self.ice.is_some() {
             41
@@ -23,27 +50,23 @@ impl Rule for RuleGasCount {
         }
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
-        if let Some(ice) = self.ice {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        if self.ice.is_some() {
             evaluate_unsafe!(galaxy, evaluation, |sp| {
-                let mut count = 0;
-                for planet in sp.get_planets() {
-                    let theme = planet.get_theme();
-                    if planet.is_gas_giant() && (theme.temperature < 0.0) == ice {
-                        count += 1;
-                    }
-                }
-                self.condition.eval(count as f32)
+                self.condition.eval(self.get_value(sp))
             })
         } else {
             evaluate_safe!(galaxy, evaluation, |sp| {
-                let planets = sp.get_planets();
-                let targets = planets
-                    .iter()
-                    .filter(|planet| planet.is_gas_giant())
-                    .count();
-                self.condition.eval(targets as f32)
+                self.condition.eval(self.get_value(sp))
             })
         }
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
@@ -1,7 +1,9 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_unsafe;
 use serde::{Deserialize, Serialize};
 
@@ -12,26 +14,44 @@ pub struct RuleGasRate {
     pub condition: Condition,
 }
 
+impl RuleGasRate {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        let mut total = 0.0;
+        for planet in sp.get_planets() {
+            if !planet.is_gas_giant() {
+                planet.get_theme();
+                continue;
+            }
+            for (gas_type, rate) in planet.get_gases() {
+                if *gas_type == self.gas_type {
+                    total += *rate
+                }
+            }
+        }
+        total
+    }
+}
+
 impl Rule for RuleGasRate {
+    fn get_name(&self) -> &'static str {
+        "GasRate"
+    }
+
     fn get_priority(&self) -> i32 {
         50
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_unsafe!(galaxy, evaluation, |sp| {
-            let mut total = 0.0;
-            for planet in sp.get_planets() {
-                if !planet.is_gas_giant() {
-                    planet.get_theme();
-                    continue;
-                }
-                for (gas_type, rate) in planet.get_gases() {
-                    if *gas_type == self.gas_type {
-                        total += *rate
-                    }
-                }
-            }
-            self.condition.eval(total)
+            self.condition.eval(self.get_value(sp))
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
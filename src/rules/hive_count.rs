@@ -1,7 +1,9 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_safe;
 use serde::{Deserialize, Serialize};
 
@@ -12,7 +14,22 @@ pub struct RuleHiveCount {
     pub initial: bool,
 }
 
+impl RuleHiveCount {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        let count = if self.initial {
+            sp.star.get_initial_hive_count()
+        } else {
+            sp.star.get_max_hive_count()
+        };
+        count as f32
+    }
+}
+
 impl Rule for RuleHiveCount {
+    fn get_name(&self) -> &'static str {
+        "HiveCount"
+    }
+
     fn get_priority(&self) -> i32 {
         if self.initial {
             23
@@ -21,14 +38,17 @@ impl Rule for RuleHiveCount {
         }
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_safe!(galaxy, evaluation, |sp| {
-            let count = if self.initial {
-                sp.star.get_initial_hive_count()
-            } else {
-                sp.star.get_max_hive_count()
-            };
-            self.condition.eval(count as f32)
+            self.condition.eval(self.get_value(sp))
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
@@ -0,0 +1,60 @@
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::explain_with;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Explanation;
+use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
+use crate::evaluate_safe;
+use serde::{Deserialize, Serialize};
+
+/// Fewest hops from the birth star when no single hop may be longer than
+/// `max_hop` light years. Unreachable stars measure as infinity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleHopCount {
+    pub max_hop: f64,
+    pub condition: Condition,
+}
+
+impl RuleHopCount {
+    fn get_hops(&self, galaxy: &Galaxy) -> Vec<f32> {
+        galaxy
+            .get_graph()
+            .hop_counts(0, self.max_hop)
+            .into_iter()
+            .map(|hops| hops.map_or(f32::INFINITY, |h| h as f32))
+            .collect()
+    }
+}
+
+impl Rule for RuleHopCount {
+    fn get_name(&self) -> &'static str {
+        "HopCount"
+    }
+
+    fn get_priority(&self) -> i32 {
+        17
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        let hops = self.get_hops(galaxy);
+        evaluate_safe!(galaxy, evaluation, |sp| {
+            self.condition.eval(hops[sp.star.index])
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_hops(galaxy)[sp.star.index])
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let hops = self.get_hops(galaxy);
+        explain_with(self, galaxy, evaluation, |sp| Some(hops[sp.star.index]))
+    }
+}
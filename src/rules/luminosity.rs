@@ -1,7 +1,9 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_safe;
 use serde::{Deserialize, Serialize};
 
@@ -11,14 +13,32 @@ pub struct RuleLuminosity {
     pub condition: Condition,
 }
 
+impl RuleLuminosity {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        sp.star.get_luminosity()
+    }
+}
+
 impl Rule for RuleLuminosity {
+    fn get_name(&self) -> &'static str {
+        "Luminosity"
+    }
+
     fn get_priority(&self) -> i32 {
         20
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_safe!(galaxy, evaluation, |sp| {
-            self.condition.eval(sp.star.get_luminosity())
+            self.condition.eval(self.get_value(sp))
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
@@ -1,3 +1,4 @@
+pub mod aggregate;
 pub mod and;
 pub mod average_vein_amount;
 pub mod birth;
@@ -7,9 +8,14 @@ pub mod dyson_radius;
 pub mod gas_count;
 pub mod gas_rate;
 pub mod hive_count;
+pub mod hop_count;
 pub mod luminosity;
+pub mod nearest;
+pub mod nearest_neighbor;
+pub mod neighbor_count;
 pub mod ocean_type;
 pub mod or;
+pub mod planet;
 pub mod planet_count;
 pub mod planet_in_dyson_count;
 pub mod satellite_count;
@@ -18,4 +24,7 @@ pub mod spectr_distance;
 pub mod star_type;
 pub mod theme_id;
 pub mod tidal_lock_count;
+pub mod vein_birth_distance;
+pub mod vein_cluster;
+pub mod vein_pair_distance;
 pub mod x_distance;
@@ -0,0 +1,83 @@
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Explanation;
+use crate::data::rule::Rule;
+use crate::data::rule::StarExplanation;
+use crate::evaluate_safe;
+
+/// Distance to the nearest other star that satisfies `rule`.
+/// Combine with `Birth` to measure from the birth star only.
+pub struct RuleNearest {
+    pub rule: Box<dyn Rule + Send + Sync>,
+    pub condition: Condition,
+}
+
+impl RuleNearest {
+    fn get_distances(&self, galaxy: &Galaxy, targets: StarMask) -> Vec<f32> {
+        let graph = galaxy.get_graph();
+        (0..galaxy.stars.len())
+            .map(|index| {
+                graph
+                    .nearest_in(index, &targets)
+                    .map_or(f32::INFINITY, |distance| distance as f32)
+            })
+            .collect()
+    }
+}
+
+impl Rule for RuleNearest {
+    fn get_name(&self) -> &'static str {
+        "Nearest"
+    }
+
+    fn get_priority(&self) -> i32 {
+        self.rule.get_priority().max(16)
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        let targets = self
+            .rule
+            .evaluate(galaxy, &Evaluation::new(galaxy.stars.len()));
+        if targets.is_empty() {
+            return StarMask::new();
+        }
+        let distances = self.get_distances(galaxy, targets);
+        evaluate_safe!(galaxy, evaluation, |sp| {
+            self.condition.eval(distances[sp.star.index])
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn adapt(&self) {
+        self.rule.adapt();
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let child = self
+            .rule
+            .explain(galaxy, &Evaluation::new(galaxy.stars.len()));
+        let distances = self.get_distances(galaxy, child.result);
+        let result = evaluate_safe!(galaxy, evaluation, |sp| {
+            self.condition.eval(distances[sp.star.index])
+        });
+        let mut explanation = Explanation::new(self.get_name(), result);
+        explanation.condition = Some(self.condition.clone());
+        for (index, distance) in distances.iter().enumerate().take(evaluation.get_len()) {
+            if evaluation.is_known(index) {
+                continue;
+            }
+            explanation.stars.push(StarExplanation {
+                index,
+                value: Some(*distance),
+                passed: result.contains(index),
+            });
+        }
+        explanation.children = vec![child];
+        explanation
+    }
+}
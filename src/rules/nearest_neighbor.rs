@@ -0,0 +1,49 @@
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
+use crate::evaluate_safe;
+use serde::{Deserialize, Serialize};
+
+/// Distance to the `k`-th nearest other star.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleNearestNeighbor {
+    pub k: usize,
+    pub condition: Condition,
+}
+
+impl RuleNearestNeighbor {
+    fn get_value(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> f32 {
+        galaxy
+            .get_graph()
+            .nearest_distance(sp.star.index, self.k)
+            .map_or(f32::INFINITY, |distance| distance as f32)
+    }
+}
+
+impl Rule for RuleNearestNeighbor {
+    fn get_name(&self) -> &'static str {
+        "NearestNeighbor"
+    }
+
+    fn get_priority(&self) -> i32 {
+        16
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        evaluate_safe!(galaxy, evaluation, |sp| {
+            self.condition.eval(self.get_value(galaxy, sp))
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(galaxy, sp))
+    }
+}
@@ -0,0 +1,46 @@
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
+use crate::evaluate_safe;
+use serde::{Deserialize, Serialize};
+
+/// Number of other stars within `radius` light years.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleNeighborCount {
+    pub radius: f64,
+    pub condition: Condition,
+}
+
+impl RuleNeighborCount {
+    fn get_value(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> f32 {
+        galaxy.get_graph().count_within(sp.star.index, self.radius) as f32
+    }
+}
+
+impl Rule for RuleNeighborCount {
+    fn get_name(&self) -> &'static str {
+        "NeighborCount"
+    }
+
+    fn get_priority(&self) -> i32 {
+        16
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        evaluate_safe!(galaxy, evaluation, |sp| {
+            self.condition.eval(self.get_value(galaxy, sp))
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(galaxy, sp))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
@@ -11,6 +12,10 @@ pub struct RuleOceanType {
 }
 
 impl Rule for RuleOceanType {
+    fn get_name(&self) -> &'static str {
+        "OceanType"
+    }
+
     fn get_priority(&self) -> i32 {
         42
     }
@@ -18,7 +23,7 @@ impl Rule for RuleOceanType {
         &self,
         galaxy: &Galaxy,
         evaluation: &Evaluation,
-    ) -> u64 {
+    ) -> StarMask {
         evaluate_unsafe!(galaxy, evaluation, |sp| {
             let mut found = false;
             for planet in sp.get_planets() {
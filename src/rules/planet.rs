@@ -0,0 +1,58 @@
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::planet_rule::PlanetRule;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
+use crate::{evaluate_safe, evaluate_unsafe};
+
+/// Matches stars where the number of planets satisfying `rule` meets `condition`.
+pub struct RulePlanet {
+    pub rule: Box<dyn PlanetRule + Send + Sync>,
+    pub condition: Condition,
+}
+
+impl RulePlanet {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        let planets = sp.get_planets();
+        if planets.is_empty() {
+            return 0.0;
+        }
+        if self.rule.needs_theme() && !sp.is_safe() {
+            sp.load_planets();
+        }
+        let evaluation = Evaluation::new(planets.len());
+        self.rule.evaluate(planets, &evaluation).count_ones() as f32
+    }
+}
+
+impl Rule for RulePlanet {
+    fn get_name(&self) -> &'static str {
+        "Planet"
+    }
+
+    fn get_priority(&self) -> i32 {
+        self.rule.get_priority()
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        if self.rule.needs_theme() {
+            evaluate_unsafe!(galaxy, evaluation, |sp| {
+                self.condition.eval(self.get_value(sp))
+            })
+        } else {
+            evaluate_safe!(galaxy, evaluation, |sp| {
+                self.condition.eval(self.get_value(sp))
+            })
+        }
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
+}
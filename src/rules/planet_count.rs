@@ -1,7 +1,9 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_safe;
 use serde::{Deserialize, Serialize};
 
@@ -13,20 +15,38 @@ pub struct RulePlanetCount {
     pub condition: Condition,
 }
 
+impl RulePlanetCount {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        let planets = sp.get_planets();
+        let len = if self.exclude_giant {
+            planets.iter().filter(|p| !p.is_gas_giant()).count()
+        } else {
+            planets.len()
+        };
+        len as f32
+    }
+}
+
 impl Rule for RulePlanetCount {
+    fn get_name(&self) -> &'static str {
+        "PlanetCount"
+    }
+
     fn get_priority(&self) -> i32 {
         30
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_safe!(galaxy, evaluation, |sp| {
-            let planets = sp.get_planets();
-            let len = if self.exclude_giant {
-                planets.iter().filter(|p| !p.is_gas_giant()).count()
-            } else {
-                planets.len()
-            };
-            self.condition.eval(len as f32)
+            self.condition.eval(self.get_value(sp))
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
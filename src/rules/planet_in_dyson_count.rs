@@ -1,7 +1,9 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_safe;
 use serde::{Deserialize, Serialize};
 
@@ -11,23 +13,40 @@ pub struct RulePlanetInDysonCount {
     pub include_giant: bool,
     pub condition: Condition,
 }
+
+impl RulePlanetInDysonCount {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        let dyson_radius = sp.star.get_dyson_radius() as f32;
+        sp.get_planets()
+            .iter()
+            .filter(|planet| {
+                (self.include_giant || !planet.is_gas_giant())
+                    && planet.get_sun_distance() * 40000.0 < dyson_radius
+            })
+            .count() as f32
+    }
+}
+
 impl Rule for RulePlanetInDysonCount {
+    fn get_name(&self) -> &'static str {
+        "PlanetInDysonCount"
+    }
+
     fn get_priority(&self) -> i32 {
         34
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_safe!(galaxy, evaluation, |sp| {
-            let planets = sp.get_planets();
-            let dyson_radius = sp.star.get_dyson_radius() as f32;
-            let targets = planets
-                .iter()
-                .filter(|planet| {
-                    (self.include_giant || !planet.is_gas_giant())
-                        && planet.get_sun_distance() * 40000.0 < dyson_radius
-                })
-                .count();
-            self.condition.eval(targets as f32)
+            self.condition.eval(self.get_value(sp))
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
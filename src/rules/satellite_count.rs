@@ -1,7 +1,9 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_safe;
 use serde::{Deserialize, Serialize};
 
@@ -11,19 +13,35 @@ pub struct RuleSatelliteCount {
     pub condition: Condition,
 }
 
+impl RuleSatelliteCount {
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        sp.get_planets()
+            .iter()
+            .filter(|planet| planet.has_orbit_around())
+            .count() as f32
+    }
+}
+
 impl Rule for RuleSatelliteCount {
+    fn get_name(&self) -> &'static str {
+        "SatelliteCount"
+    }
+
     fn get_priority(&self) -> i32 {
         31
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_safe!(galaxy, evaluation, |sp| {
-            let planets = sp.get_planets();
-            let targets = planets
-                .iter()
-                .filter(|planet| planet.has_orbit_around())
-                .count();
-            self.condition.eval(targets as f32)
+            self.condition.eval(self.get_value(sp))
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
 }
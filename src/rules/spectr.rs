@@ -1,3 +1,4 @@
+use crate::data::bitset::StarMask;
 use crate::data::enums::SpectrType;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Evaluation;
@@ -12,11 +13,15 @@ pub struct RuleSpectr {
 }
 
 impl Rule for RuleSpectr {
+    fn get_name(&self) -> &'static str {
+        "Spectr"
+    }
+
     fn get_priority(&self) -> i32 {
         21
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_safe!(galaxy, evaluation, |sp| {
             self.spectr.contains(&sp.star.get_spectr())
         })
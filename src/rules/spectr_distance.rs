@@ -1,7 +1,10 @@
+use crate::data::bitset::StarMask;
 use crate::data::enums::SpectrType;
 use crate::data::galaxy::Galaxy;
+use crate::data::rule::explain_with;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
+use crate::data::rule::Explanation;
 use crate::data::rule::Rule;
 use crate::data::star_planets::StarWithPlanets;
 use crate::evaluate_safe;
@@ -15,34 +18,63 @@ pub struct RuleSpectrDistance {
     pub count_condition: Condition,
 }
 
-impl Rule for RuleSpectrDistance {
-    fn get_priority(&self) -> i32 {
-        15
+impl RuleSpectrDistance {
+    fn count_near(&self, good_stars: &[&StarWithPlanets], sp: &StarWithPlanets) -> usize {
+        let star = &sp.star;
+        good_stars
+            .iter()
+            .filter(|sp2| {
+                sp2.star.index != star.index
+                    && self
+                        .distance_condition
+                        .eval(star.position.distance_from(&sp2.star.position) as f32)
+            })
+            .count()
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
-        let good_stars: Vec<&StarWithPlanets> = galaxy
+    fn get_good_stars<'a, 'b>(&self, galaxy: &'a Galaxy<'b>) -> Vec<&'a StarWithPlanets<'b>> {
+        galaxy
             .stars
             .iter()
             .filter(|sp| sp.star.get_spectr() == self.spectr)
-            .collect();
+            .collect()
+    }
+}
+
+impl Rule for RuleSpectrDistance {
+    fn get_name(&self) -> &'static str {
+        "SpectrDistance"
+    }
+
+    fn get_priority(&self) -> i32 {
+        15
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        let good_stars = self.get_good_stars(galaxy);
 
         if good_stars.is_empty() {
-            return 0;
+            return StarMask::new();
         }
 
         evaluate_safe!(galaxy, evaluation, |sp| {
-            let star = &sp.star;
-            let count = good_stars
-                .iter()
-                .filter(|sp2| {
-                    sp2.star.index != star.index
-                        && self
-                            .distance_condition
-                            .eval(star.position.distance_from(&sp2.star.position) as f32)
-                })
-                .count();
-            self.count_condition.eval(count as f32)
+            self.count_condition
+                .eval(self.count_near(&good_stars, sp) as f32)
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.count_condition)
+    }
+
+    fn measure(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.count_near(&self.get_good_stars(galaxy), sp) as f32)
+    }
+
+    fn explain(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> Explanation {
+        let good_stars = self.get_good_stars(galaxy);
+        explain_with(self, galaxy, evaluation, |sp| {
+            Some(self.count_near(&good_stars, sp) as f32)
         })
     }
 }
@@ -1,3 +1,4 @@
+use crate::data::bitset::StarMask;
 use crate::data::enums::StarType;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Evaluation;
@@ -12,11 +13,15 @@ pub struct RuleStarType {
 }
 
 impl Rule for RuleStarType {
+    fn get_name(&self) -> &'static str {
+        "StarType"
+    }
+
     fn get_priority(&self) -> i32 {
         11
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_safe!(galaxy, evaluation, |sp| {
             self.star_type.contains(&sp.star.star_type)
         })
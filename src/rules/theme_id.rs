@@ -1,3 +1,4 @@
+use crate::data::bitset::StarMask;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
@@ -11,11 +12,15 @@ pub struct RuleThemeId {
 }
 
 impl Rule for RuleThemeId {
+    fn get_name(&self) -> &'static str {
+        "ThemeId"
+    }
+
     fn get_priority(&self) -> i32 {
         40
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         evaluate_unsafe!(galaxy, evaluation, |sp| {
             let mut found = false;
             for planet in sp.get_planets() {
@@ -0,0 +1,60 @@
+use crate::data::bitset::StarMask;
+use crate::data::enums::VeinType;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
+use crate::evaluate_unsafe;
+use serde::{Deserialize, Serialize};
+
+/// Distance in meters along the surface from the landing point to the
+/// nearest `vein` node. Only the birth planet has a landing point.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleVeinBirthDistance {
+    pub vein: VeinType,
+    pub condition: Condition,
+}
+
+impl RuleVeinBirthDistance {
+    fn get_value(&self, sp: &StarWithPlanets) -> Option<f32> {
+        if !sp.star.is_birth() {
+            return None;
+        }
+        sp.get_planets().iter().find_map(|planet| {
+            let birth_point = planet.get_birth_point()?;
+            planet
+                .get_vein_nodes(&self.vein)
+                .iter()
+                .map(|node| node.angle_to(birth_point))
+                .min_by(f32::total_cmp)
+                .map(|angle| planet.surface_distance(angle))
+        })
+    }
+}
+
+impl Rule for RuleVeinBirthDistance {
+    fn get_name(&self) -> &'static str {
+        "VeinBirthDistance"
+    }
+
+    fn get_priority(&self) -> i32 {
+        102
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        evaluate_unsafe!(galaxy, evaluation, |sp| {
+            self.get_value(sp)
+                .is_some_and(|value| self.condition.eval(value))
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        self.get_value(sp)
+    }
+}
@@ -0,0 +1,68 @@
+use crate::data::bitset::StarMask;
+use crate::data::enums::VeinType;
+use crate::data::galaxy::Galaxy;
+use crate::data::planet::Planet;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
+use crate::evaluate_unsafe;
+use serde::{Deserialize, Serialize};
+
+/// Largest number of `vein` nodes on one planet that lie within `angle`
+/// degrees of one of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleVeinCluster {
+    pub vein: VeinType,
+    pub angle: f32,
+    pub condition: Condition,
+}
+
+impl RuleVeinCluster {
+    fn get_cluster_size(&self, planet: &Planet) -> usize {
+        let nodes = planet.get_vein_nodes(&self.vein);
+        nodes
+            .iter()
+            .map(|center| {
+                nodes
+                    .iter()
+                    .filter(|node| node.angle_to(&center.direction) <= self.angle)
+                    .count()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn get_value(&self, sp: &StarWithPlanets) -> f32 {
+        sp.get_planets()
+            .iter()
+            .map(|planet| self.get_cluster_size(planet))
+            .max()
+            .unwrap_or(0) as f32
+    }
+}
+
+impl Rule for RuleVeinCluster {
+    fn get_name(&self) -> &'static str {
+        "VeinCluster"
+    }
+
+    fn get_priority(&self) -> i32 {
+        102
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        evaluate_unsafe!(galaxy, evaluation, |sp| {
+            self.condition.eval(self.get_value(sp))
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        Some(self.get_value(sp))
+    }
+}
@@ -0,0 +1,66 @@
+use crate::data::bitset::StarMask;
+use crate::data::enums::VeinType;
+use crate::data::galaxy::Galaxy;
+use crate::data::planet::Planet;
+use crate::data::rule::Condition;
+use crate::data::rule::Evaluation;
+use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
+use crate::evaluate_unsafe;
+use serde::{Deserialize, Serialize};
+
+/// Shortest distance in meters along the surface between a node of the first
+/// and a node of the second vein type on the same planet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleVeinPairDistance {
+    pub veins: [VeinType; 2],
+    pub condition: Condition,
+}
+
+impl RuleVeinPairDistance {
+    fn get_distance(&self, planet: &Planet) -> Option<f32> {
+        let first = planet.get_vein_nodes(&self.veins[0]);
+        if first.is_empty() {
+            return None;
+        }
+        let second = planet.get_vein_nodes(&self.veins[1]);
+        first
+            .iter()
+            .flat_map(|a| second.iter().map(|b| a.angle_to(&b.direction)))
+            .min_by(f32::total_cmp)
+            .map(|angle| planet.surface_distance(angle))
+    }
+
+    fn get_value(&self, sp: &StarWithPlanets) -> Option<f32> {
+        sp.get_planets()
+            .iter()
+            .filter_map(|planet| self.get_distance(planet))
+            .min_by(f32::total_cmp)
+    }
+}
+
+impl Rule for RuleVeinPairDistance {
+    fn get_name(&self) -> &'static str {
+        "VeinPairDistance"
+    }
+
+    fn get_priority(&self) -> i32 {
+        102
+    }
+
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
+        evaluate_unsafe!(galaxy, evaluation, |sp| {
+            self.get_value(sp)
+                .is_some_and(|value| self.condition.eval(value))
+        })
+    }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    fn measure(&self, _: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        self.get_value(sp)
+    }
+}
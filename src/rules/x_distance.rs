@@ -1,8 +1,10 @@
+use crate::data::bitset::StarMask;
 use crate::data::enums::StarType;
 use crate::data::galaxy::Galaxy;
 use crate::data::rule::Condition;
 use crate::data::rule::Evaluation;
 use crate::data::rule::Rule;
+use crate::data::star_planets::StarWithPlanets;
 use crate::data::vector3::Vector3;
 use crate::evaluate_safe;
 use serde::{Deserialize, Serialize};
@@ -15,11 +17,15 @@ pub struct RuleXDistance {
 }
 
 impl Rule for RuleXDistance {
+    fn get_name(&self) -> &'static str {
+        "XDistance"
+    }
+
     fn get_priority(&self) -> i32 {
         14
     }
 
-    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> u64 {
+    fn evaluate(&self, galaxy: &Galaxy, evaluation: &Evaluation) -> StarMask {
         let x_stars: Vec<&Vector3> = galaxy
             .stars
             .iter()
@@ -31,7 +37,7 @@ impl Rule for RuleXDistance {
             .collect();
 
         if x_stars.is_empty() {
-            return 0;
+            return StarMask::new();
         }
 
         evaluate_safe!(galaxy, evaluation, |sp| {
@@ -47,4 +53,26 @@ impl Rule for RuleXDistance {
             }
         })
     }
+
+    fn get_condition(&self) -> Option<&Condition> {
+        Some(&self.condition)
+    }
+
+    /// Distance to the nearest black hole or neutron star, or to the farthest
+    /// with `all`.
+    fn measure(&self, galaxy: &Galaxy, sp: &StarWithPlanets) -> Option<f32> {
+        let distances = galaxy
+            .stars
+            .iter()
+            .filter(|other| {
+                other.star.star_type == StarType::BlackHole
+                    || other.star.star_type == StarType::NeutronStar
+            })
+            .map(|other| sp.star.position.distance_from(&other.star.position) as f32);
+        if self.all {
+            distances.max_by(f32::total_cmp)
+        } else {
+            distances.min_by(f32::total_cmp)
+        }
+    }
 }
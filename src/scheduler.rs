@@ -0,0 +1,217 @@
+use crate::control::SearchControl;
+use std::collections::{HashMap, VecDeque};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex};
+
+/// Number of seeds a single scheduled job evaluates.
+pub const JOB_SIZE: usize = 64;
+
+pub type Job = Box<dyn FnOnce() + Send>;
+
+type BatchDone = Box<dyn FnOnce(Option<Vec<i32>>) + Send>;
+
+struct PendingBatch {
+    results: Vec<Vec<i32>>,
+    remaining: usize,
+    failed: bool,
+    done: Option<BatchDone>,
+}
+
+impl PendingBatch {
+    fn finish(&mut self, index: usize, result: Option<Vec<i32>>) {
+        match result {
+            Some(result) => self.results[index] = result,
+            None => self.failed = true,
+        }
+        self.remaining -= 1;
+        if self.remaining == 0 {
+            let result = (!self.failed).then(|| self.results.concat());
+            (self.done.take().unwrap())(result);
+        }
+    }
+}
+
+struct Queue {
+    /// Most jobs of this queue that may run at the same time, and its weight
+    /// when the threads are shared with other queues.
+    concurrency: usize,
+    control: Arc<SearchControl>,
+    running: usize,
+    /// Virtual time consumed so far, the queue with the lowest runs next.
+    pass: f64,
+    jobs: VecDeque<Job>,
+    closed: bool,
+}
+
+impl Queue {
+    fn is_ready(&self) -> bool {
+        !self.jobs.is_empty() && self.running < self.concurrency && !self.control.is_paused()
+    }
+
+    fn is_idle(&self) -> bool {
+        self.jobs.is_empty() && self.running == 0
+    }
+}
+
+#[derive(Default)]
+struct State {
+    running: usize,
+    next_id: u64,
+    queues: HashMap<u64, Queue>,
+}
+
+/// Shares the global rayon pool fairly between the search sessions of every
+/// connection, instead of each of them building its own pool.
+///
+/// Jobs are dispatched by stride scheduling: each queue advances its pass by
+/// `1 / concurrency` per job, so busy queues get threads in proportion to
+/// their concurrency, and no more than it.
+pub struct Scheduler {
+    threads: usize,
+    state: Mutex<State>,
+}
+
+impl Scheduler {
+    pub fn new(threads: usize) -> Arc<Self> {
+        Arc::new(Self {
+            threads: threads.max(1),
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    pub fn get_threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Jobs of a paused `control` are held back until `dispatch` is called
+    /// again after resuming.
+    pub fn register(&self, concurrency: usize, control: Arc<SearchControl>) -> u64 {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        state.queues.insert(
+            id,
+            Queue {
+                concurrency: concurrency.max(1),
+                control,
+                running: 0,
+                pass: 0.0,
+                jobs: VecDeque::new(),
+                closed: false,
+            },
+        );
+        id
+    }
+
+    /// The queue is dropped once its remaining jobs are done.
+    pub fn unregister(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(queue) = state.queues.get_mut(&id) {
+            queue.closed = true;
+            if queue.is_idle() {
+                state.queues.remove(&id);
+            }
+        }
+    }
+
+    pub fn submit(self: &Arc<Self>, id: u64, jobs: Vec<Job>) {
+        {
+            let mut state = self.state.lock().unwrap();
+            // A queue that was idle starts at the current virtual time, rather
+            // than catching up on the time it did not use.
+            let now = state
+                .queues
+                .values()
+                .filter(|queue| !queue.is_idle())
+                .map(|queue| queue.pass)
+                .min_by(f64::total_cmp);
+            let Some(queue) = state.queues.get_mut(&id) else {
+                return;
+            };
+            if queue.is_idle() {
+                if let Some(now) = now {
+                    queue.pass = queue.pass.max(now);
+                }
+            }
+            queue.jobs.extend(jobs);
+        }
+        self.dispatch();
+    }
+
+    /// Splits `seeds` into jobs of `JOB_SIZE` seeds for queue `id`. Once all
+    /// of them ran, `done` receives what `evaluate` returned for each job in
+    /// input order, or `None` if one of them panicked.
+    pub fn submit_batch<E, D>(self: &Arc<Self>, id: u64, seeds: Vec<i32>, evaluate: E, done: D)
+    where
+        E: Fn(Vec<i32>) -> Vec<i32> + Send + Sync + 'static,
+        D: FnOnce(Option<Vec<i32>>) + Send + 'static,
+    {
+        // An empty batch still gets one job, so `done` is never called here.
+        let chunks: Vec<Vec<i32>> = if seeds.is_empty() {
+            vec![vec![]]
+        } else {
+            seeds.chunks(JOB_SIZE).map(|chunk| chunk.to_vec()).collect()
+        };
+        let evaluate = Arc::new(evaluate);
+        let batch = Arc::new(Mutex::new(PendingBatch {
+            results: vec![vec![]; chunks.len()],
+            remaining: chunks.len(),
+            failed: false,
+            done: Some(Box::new(done)),
+        }));
+        let jobs = chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, chunk)| {
+                let evaluate = Arc::clone(&evaluate);
+                let batch = Arc::clone(&batch);
+                Box::new(move || {
+                    let result = panic::catch_unwind(AssertUnwindSafe(|| evaluate(chunk))).ok();
+                    batch.lock().unwrap().finish(index, result);
+                }) as Job
+            })
+            .collect();
+        self.submit(id, jobs);
+    }
+
+    /// Starts queued jobs while there are free threads.
+    pub fn dispatch(self: &Arc<Self>) {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        while state.running < self.threads {
+            let Some((&id, queue)) = state
+                .queues
+                .iter_mut()
+                .filter(|(_, queue)| queue.is_ready())
+                .min_by(|(a_id, a), (b_id, b)| a.pass.total_cmp(&b.pass).then(a_id.cmp(b_id)))
+            else {
+                break;
+            };
+            let job = queue.jobs.pop_front().unwrap();
+            queue.running += 1;
+            queue.pass += 1.0 / queue.concurrency as f64;
+            state.running += 1;
+            let scheduler = Arc::clone(self);
+            rayon::spawn(move || {
+                if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+                    eprintln!("Warning: A search job failed.");
+                }
+                scheduler.finish(id);
+            });
+        }
+    }
+
+    fn finish(self: &Arc<Self>, id: u64) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.running -= 1;
+            if let Some(queue) = state.queues.get_mut(&id) {
+                queue.running -= 1;
+                if queue.closed && queue.is_idle() {
+                    state.queues.remove(&id);
+                }
+            }
+        }
+        self.dispatch();
+    }
+}
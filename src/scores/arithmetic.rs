@@ -0,0 +1,35 @@
+use crate::data::galaxy::Galaxy;
+use crate::data::score::Score;
+
+pub struct ScoreConstant {
+    pub value: f32,
+}
+
+impl Score for ScoreConstant {
+    fn score(&self, _: &Galaxy) -> f32 {
+        self.value
+    }
+}
+
+pub struct ScoreAdd {
+    pub scores: Vec<Box<dyn Score + Send + Sync>>,
+}
+
+impl Score for ScoreAdd {
+    fn score(&self, galaxy: &Galaxy) -> f32 {
+        self.scores.iter().map(|score| score.score(galaxy)).sum()
+    }
+}
+
+pub struct ScoreMultiply {
+    pub scores: Vec<Box<dyn Score + Send + Sync>>,
+}
+
+impl Score for ScoreMultiply {
+    fn score(&self, galaxy: &Galaxy) -> f32 {
+        self.scores
+            .iter()
+            .map(|score| score.score(galaxy))
+            .product()
+    }
+}
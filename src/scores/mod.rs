@@ -0,0 +1,3 @@
+pub mod arithmetic;
+pub mod star_value;
+pub mod stars;
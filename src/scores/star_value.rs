@@ -0,0 +1,82 @@
+use crate::data::enums::VeinType;
+use crate::data::star_planets::StarWithPlanets;
+use serde::{Deserialize, Serialize};
+
+/// A numeric property of a single star system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum StarValue {
+    Luminosity,
+    DysonRadius,
+    BirthDistance,
+    PlanetCount,
+    SatelliteCount,
+    GasGiantCount,
+    TidalLockCount,
+    InitialHiveCount,
+    MaxHiveCount,
+    #[serde(rename_all = "camelCase")]
+    AverageVein {
+        vein: VeinType,
+    },
+    #[serde(rename_all = "camelCase")]
+    ActualVein {
+        vein: VeinType,
+    },
+    #[serde(rename_all = "camelCase")]
+    GasRate {
+        gas_type: i32,
+    },
+}
+
+impl StarValue {
+    /// Whether measuring the value generates planet themes, in which case
+    /// stars must be visited in index order (see `evaluate_unsafe!`).
+    pub fn needs_theme(&self) -> bool {
+        matches!(
+            self,
+            StarValue::AverageVein { .. }
+                | StarValue::ActualVein { .. }
+                | StarValue::GasRate { .. }
+        )
+    }
+
+    pub fn measure(&self, sp: &StarWithPlanets) -> f32 {
+        match self {
+            StarValue::Luminosity => sp.star.get_luminosity(),
+            StarValue::DysonRadius => sp.star.get_dyson_radius() as f32,
+            StarValue::BirthDistance => sp.star.position.magnitude() as f32,
+            StarValue::PlanetCount => sp.get_planets().len() as f32,
+            StarValue::SatelliteCount => sp
+                .get_planets()
+                .iter()
+                .filter(|planet| planet.has_orbit_around())
+                .count() as f32,
+            StarValue::GasGiantCount => sp
+                .get_planets()
+                .iter()
+                .filter(|planet| planet.is_gas_giant())
+                .count() as f32,
+            StarValue::TidalLockCount => sp
+                .get_planets()
+                .iter()
+                .filter(|planet| planet.is_tidal_locked())
+                .count() as f32,
+            StarValue::InitialHiveCount => sp.star.get_initial_hive_count() as f32,
+            StarValue::MaxHiveCount => sp.star.get_max_hive_count() as f32,
+            StarValue::AverageVein { vein } => sp.get_avg_vein(vein),
+            StarValue::ActualVein { vein } => sp.get_actual_vein(vein),
+            StarValue::GasRate { gas_type } => {
+                let mut total = 0.0;
+                for planet in sp.get_planets() {
+                    for (item, rate) in planet.get_gases() {
+                        if item == gas_type {
+                            total += *rate;
+                        }
+                    }
+                }
+                total
+            }
+        }
+    }
+}
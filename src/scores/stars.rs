@@ -0,0 +1,61 @@
+use super::star_value::StarValue;
+use crate::data::bitset::StarMask;
+use crate::data::galaxy::Galaxy;
+use crate::data::rule::{Evaluation, Rule};
+use crate::data::score::Score;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Aggregate {
+    Sum,
+    Min,
+    Max,
+    Average,
+    Count,
+}
+
+/// Aggregates a star value over every star accepted by `filter`
+/// (or every star when there is no filter).
+pub struct ScoreStars {
+    pub aggregate: Aggregate,
+    pub value: StarValue,
+    pub filter: Option<Box<dyn Rule + Send + Sync>>,
+}
+
+impl Score for ScoreStars {
+    fn score(&self, galaxy: &Galaxy) -> f32 {
+        let mask = match &self.filter {
+            Some(rule) => rule.evaluate(galaxy, &Evaluation::new(galaxy.stars.len())),
+            None => StarMask::first(galaxy.stars.len()),
+        };
+        let needs_theme = self.aggregate != Aggregate::Count && self.value.needs_theme();
+        let mut count = 0;
+        let mut total = 0.0;
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        for (index, sp) in galaxy.stars.iter().enumerate() {
+            if needs_theme && !sp.is_safe() {
+                sp.load_planets();
+            }
+            if !mask.contains(index) {
+                continue;
+            }
+            count += 1;
+            if self.aggregate == Aggregate::Count {
+                continue;
+            }
+            let value = self.value.measure(sp);
+            total += value;
+            min = min.min(value);
+            max = max.max(value);
+        }
+        match self.aggregate {
+            Aggregate::Sum => total,
+            Aggregate::Count => count as f32,
+            _ if count == 0 => 0.0,
+            Aggregate::Min => min,
+            Aggregate::Max => max,
+            Aggregate::Average => total / (count as f32),
+        }
+    }
+}
@@ -0,0 +1,191 @@
+use crate::data::bitset::StarMask;
+use crate::data::game_desc::GameDesc;
+use crate::data::rule::Rule;
+use crate::data::score::Score;
+use crate::transform_rules::Rules;
+use crate::transform_scores::Scores;
+use crate::worldgen::galaxy_gen::{find_stars, score_galaxy};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::ThreadPool;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::ops::Range;
+
+/// Largest seed accepted by the game, exclusive.
+pub const MAX_SEED: i32 = 100_000_000;
+
+/// Number of seeds evaluated before results are handed back to the caller.
+pub const CHUNK_SIZE: i32 = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchConfig {
+    pub game: GameDesc,
+    pub rule: Rules,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankConfig {
+    pub game: GameDesc,
+    #[serde(default)]
+    pub rule: Option<Rules>,
+    pub score: Scores,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeedMatch {
+    pub seed: i32,
+    pub indexes: Vec<usize>,
+}
+
+pub fn star_indexes(star_indexes: StarMask) -> Vec<usize> {
+    star_indexes.iter().collect()
+}
+
+/// Evaluates every seed in `start..end` on `pool`, one chunk at a time.
+///
+/// `on_chunk` receives the bounds of each finished chunk together with its
+/// matches in ascending seed order, so results can be streamed as they come.
+pub fn search_range<F, E>(
+    pool: &ThreadPool,
+    game: &GameDesc,
+    rule: &(dyn Rule + Send + Sync),
+    start: i32,
+    end: i32,
+    mut on_chunk: F,
+) -> Result<(), E>
+where
+    F: FnMut(i32, i32, Vec<SeedMatch>) -> Result<(), E>,
+{
+    let mut chunk_start = start;
+    while chunk_start < end {
+        let chunk_end = chunk_start.saturating_add(CHUNK_SIZE).min(end);
+        let matches: Vec<SeedMatch> = pool.install(|| {
+            (chunk_start..chunk_end)
+                .into_par_iter()
+                .filter_map(|seed| {
+                    let result = find_stars(seed, game, rule);
+                    if !result.is_empty() {
+                        Some(SeedMatch {
+                            seed,
+                            indexes: star_indexes(result),
+                        })
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        });
+        rule.adapt();
+        on_chunk(chunk_start, chunk_end, matches)?;
+        chunk_start = chunk_end;
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ScoredSeed {
+    pub seed: i32,
+    pub score: f32,
+}
+
+impl PartialEq for ScoredSeed {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ScoredSeed {}
+
+impl PartialOrd for ScoredSeed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ScoredSeed {
+    /// Higher scores rank higher; on a tie the lower seed wins.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.seed.cmp(&self.seed))
+    }
+}
+
+/// Keeps the `capacity` best scored seeds seen so far.
+#[derive(Debug, Clone)]
+pub struct TopK {
+    capacity: usize,
+    heap: BinaryHeap<Reverse<ScoredSeed>>,
+}
+
+impl TopK {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            heap: BinaryHeap::with_capacity(capacity + 1),
+        }
+    }
+
+    pub fn push(&mut self, item: ScoredSeed) {
+        if self.heap.len() < self.capacity {
+            self.heap.push(Reverse(item));
+        } else if self.heap.peek().is_some_and(|Reverse(worst)| item > *worst) {
+            self.heap.pop();
+            self.heap.push(Reverse(item));
+        }
+    }
+
+    pub fn merge(mut self, other: TopK) -> Self {
+        for Reverse(item) in other.heap {
+            self.push(item);
+        }
+        self
+    }
+
+    /// Best first.
+    pub fn to_sorted_vec(&self) -> Vec<ScoredSeed> {
+        let mut items: Vec<ScoredSeed> = self.heap.iter().map(|Reverse(item)| *item).collect();
+        items.sort_unstable_by(|a, b| b.cmp(a));
+        items
+    }
+}
+
+/// Scores every seed in `seeds` on `pool` and collects the best ones in `top`.
+/// `on_chunk` is called after each chunk with its end and the current collection.
+pub fn rank_range<F, E>(
+    pool: &ThreadPool,
+    game: &GameDesc,
+    rule: Option<&(dyn Rule + Send + Sync)>,
+    score: &(dyn Score + Send + Sync),
+    seeds: Range<i32>,
+    top: &mut TopK,
+    mut on_chunk: F,
+) -> Result<(), E>
+where
+    F: FnMut(i32, &TopK) -> Result<(), E>,
+{
+    let capacity = top.capacity;
+    let mut chunk_start = seeds.start;
+    while chunk_start < seeds.end {
+        let chunk_end = chunk_start.saturating_add(CHUNK_SIZE).min(seeds.end);
+        let chunk_top = pool.install(|| {
+            (chunk_start..chunk_end)
+                .into_par_iter()
+                .fold(
+                    || TopK::new(capacity),
+                    |mut acc, seed| {
+                        if let Some(score) = score_galaxy(seed, game, rule, score) {
+                            acc.push(ScoredSeed { seed, score });
+                        }
+                        acc
+                    },
+                )
+                .reduce(|| TopK::new(capacity), TopK::merge)
+        });
+        *top = std::mem::replace(top, TopK::new(0)).merge(chunk_top);
+        on_chunk(chunk_end, top)?;
+        chunk_start = chunk_end;
+    }
+    Ok(())
+}
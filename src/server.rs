@@ -0,0 +1,954 @@
+use crate::checkpoint::CheckpointWriter;
+use crate::control::{CancelToken, SearchControl, Throughput};
+use crate::data::bitset::StarMask;
+use crate::data::game_desc::GameDesc;
+use crate::data::rule::{Explanation, Rule};
+use crate::profile::transform_rules_adaptive;
+use crate::scheduler::Scheduler;
+use crate::search::{self, SeedMatch};
+use crate::transform_rules::{transform_rules, Rules};
+use crate::view::{GalaxyView, ViewOptions};
+use crate::worldgen::galaxy_diff::{diff_seeds, GalaxyDiff};
+use crate::worldgen::galaxy_gen::{explain_stars, find_stars};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::accept_async;
+use tokio_tungstenite::tungstenite::Message;
+
+#[tokio::main(flavor = "multi_thread")]
+pub async fn serve() -> Result<(), std::io::Error> {
+    println!("Starting...");
+    let listener = TcpListener::bind("127.0.0.1:62879").await?;
+    println!("Started.");
+    println!("You may now turn on native mode to search.");
+    let scheduler = Scheduler::new(rayon::current_num_threads());
+    println!(
+        "Sharing {} threads between search sessions.",
+        scheduler.get_threads()
+    );
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(accept_connection(stream, Arc::clone(&scheduler)));
+    }
+    Ok(())
+}
+
+/// Bumped whenever a message changes incompatibly.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Incoming message types this server understands, announced in `Hello`.
+const CAPABILITIES: &[&str] = &[
+    "Hello",
+    "Generate",
+    "GenerateBatch",
+    "Setup",
+    "SearchStar",
+    "SearchStarBatch",
+    "Explain",
+    "Diff",
+    "Search",
+    "Close",
+    "Cancel",
+    "Pause",
+    "Resume",
+];
+
+/// Session of `Setup`, `Search` and `Close` messages that do not name one.
+fn default_session() -> String {
+    "default".to_string()
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum IncomingMessage {
+    /// Optional handshake, answered with the server's version and capabilities.
+    Hello {
+        #[serde(default)]
+        version: u32,
+    },
+    Generate {
+        seed: i32,
+        game: GameDesc,
+        #[serde(default)]
+        options: ViewOptions,
+    },
+    /// Creates or replaces a session. Sessions of all connections share the
+    /// threads, each getting a share proportional to its `concurrency`.
+    /// Binary batches go to the session set up last.
+    Setup {
+        #[serde(default = "default_session")]
+        session: String,
+        concurrency: usize,
+        game: GameDesc,
+        rule: Rules,
+        #[serde(default)]
+        checkpoint: Option<String>,
+        #[serde(default)]
+        adaptive: bool,
+    },
+    SearchStar {
+        seed: i32,
+        game: GameDesc,
+        rule: Rules,
+    },
+    Explain {
+        seed: i32,
+        game: GameDesc,
+        rule: Rules,
+    },
+    /// Like `Generate` for many seeds. One `GenerateResult` is sent per seed
+    /// as soon as it is ready, then `BatchDone`.
+    GenerateBatch {
+        id: u64,
+        seeds: Vec<i32>,
+        game: GameDesc,
+        #[serde(default)]
+        options: ViewOptions,
+    },
+    /// Like `SearchStar` for many seeds. One `SearchStarResult` is sent per
+    /// seed as soon as it is ready, then `BatchDone`.
+    SearchStarBatch {
+        id: u64,
+        seeds: Vec<i32>,
+        game: GameDesc,
+        rule: Rules,
+    },
+    #[serde(rename_all = "camelCase")]
+    Diff {
+        from_seed: i32,
+        from_game: GameDesc,
+        to_seed: i32,
+        to_game: GameDesc,
+    },
+    /// Like a binary batch, for any session of the connection. Answered with
+    /// `SearchResult` once every seed was evaluated.
+    Search {
+        #[serde(default = "default_session")]
+        session: String,
+        id: u64,
+        seeds: Vec<i32>,
+    },
+    Close {
+        #[serde(default = "default_session")]
+        session: String,
+    },
+    /// Stops every batch of `session` sent so far, or of the whole connection.
+    Cancel {
+        #[serde(default)]
+        session: Option<String>,
+    },
+    Pause {
+        #[serde(default)]
+        session: Option<String>,
+    },
+    Resume {
+        #[serde(default)]
+        session: Option<String>,
+    },
+}
+
+impl IncomingMessage {
+    fn get_type(&self) -> &'static str {
+        match self {
+            IncomingMessage::Hello { .. } => "Hello",
+            IncomingMessage::Generate { .. } => "Generate",
+            IncomingMessage::Setup { .. } => "Setup",
+            IncomingMessage::SearchStar { .. } => "SearchStar",
+            IncomingMessage::Explain { .. } => "Explain",
+            IncomingMessage::GenerateBatch { .. } => "GenerateBatch",
+            IncomingMessage::SearchStarBatch { .. } => "SearchStarBatch",
+            IncomingMessage::Diff { .. } => "Diff",
+            IncomingMessage::Search { .. } => "Search",
+            IncomingMessage::Close { .. } => "Close",
+            IncomingMessage::Cancel { .. } => "Cancel",
+            IncomingMessage::Pause { .. } => "Pause",
+            IncomingMessage::Resume { .. } => "Resume",
+        }
+    }
+
+    fn get_id(&self) -> Option<u64> {
+        match self {
+            IncomingMessage::GenerateBatch { id, .. }
+            | IncomingMessage::SearchStarBatch { id, .. }
+            | IncomingMessage::Search { id, .. } => Some(*id),
+            _ => None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            IncomingMessage::Hello { .. }
+            | IncomingMessage::Search { .. }
+            | IncomingMessage::Close { .. }
+            | IncomingMessage::Cancel { .. }
+            | IncomingMessage::Pause { .. }
+            | IncomingMessage::Resume { .. } => Ok(()),
+            IncomingMessage::Generate { game, .. }
+            | IncomingMessage::Setup { game, .. }
+            | IncomingMessage::SearchStar { game, .. }
+            | IncomingMessage::Explain { game, .. }
+            | IncomingMessage::GenerateBatch { game, .. }
+            | IncomingMessage::SearchStarBatch { game, .. } => game.validate(),
+            IncomingMessage::Diff {
+                from_game, to_game, ..
+            } => from_game.validate().and_then(|_| to_game.validate()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+enum ErrorCode {
+    /// The text is not a known message or misses fields.
+    InvalidMessage,
+    /// The game settings were rejected by `GameDesc::validate`.
+    InvalidGame,
+    /// A search arrived before a successful `Setup` of its session.
+    NotSetUp,
+    /// A binary search batch is not a batch id followed by seeds.
+    MalformedBinary,
+    /// `Setup` could not open the checkpoint.
+    SetupFailed,
+    /// `Hello` asked for a newer protocol than `PROTOCOL_VERSION`.
+    UnsupportedVersion,
+    /// Generation failed unexpectedly.
+    Internal,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type")]
+enum OutgoingMessage {
+    Generate {
+        galaxy: GalaxyView,
+    },
+    Setup {
+        success: bool,
+    },
+    SearchStar {
+        indexes: Vec<usize>,
+    },
+    Explain {
+        explanation: Explanation,
+    },
+    Diff {
+        diff: GalaxyDiff,
+    },
+    GenerateResult {
+        id: u64,
+        seed: i32,
+        galaxy: GalaxyView,
+    },
+    SearchStarResult {
+        id: u64,
+        seed: i32,
+        indexes: Vec<usize>,
+    },
+    BatchDone {
+        id: u64,
+    },
+    SearchResult {
+        session: String,
+        id: u64,
+        seeds: Vec<i32>,
+    },
+    /// A batch stopped by `Cancel` before all of its seeds were evaluated.
+    BatchCancelled {
+        id: u64,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        session: Option<String>,
+    },
+    Close,
+    Cancel,
+    Pause,
+    Resume,
+    /// Sent for every session with seeds being evaluated, and without
+    /// `session` for `GenerateBatch` and `SearchStarBatch`.
+    #[serde(rename_all = "camelCase")]
+    Progress {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        session: Option<String>,
+        processed: u64,
+        seeds_per_second: f64,
+        paused: bool,
+    },
+    Hello {
+        version: u32,
+        server: &'static str,
+        capabilities: &'static [&'static str],
+    },
+    /// `request` is the type of the message that failed, when known, and `id`
+    /// its batch id.
+    Error {
+        code: ErrorCode,
+        message: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request: Option<&'static str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<u64>,
+    },
+}
+
+fn error_message(
+    code: ErrorCode,
+    message: impl ToString,
+    request: Option<&'static str>,
+    id: Option<u64>,
+) -> String {
+    encode(&OutgoingMessage::Error {
+        code,
+        message: message.to_string(),
+        request,
+        id,
+    })
+}
+
+fn encode(message: &OutgoingMessage) -> String {
+    serde_json::to_string(message).unwrap_or_else(|e| {
+        format!(
+            r#"{{"type":"Error","code":"Internal","message":{:?}}}"#,
+            e.to_string()
+        )
+    })
+}
+
+/// Runs `f` off the async runtime, turning a panic into an `Internal` error.
+async fn run_blocking<F>(request: &'static str, f: F) -> String
+where
+    F: FnOnce() -> String + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| error_message(ErrorCode::Internal, e, Some(request), None))
+}
+
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(30);
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Searches a batch while skipping seeds the checkpoint has already covered,
+/// then records the newly evaluated seeds. Returns matches in input order.
+/// Seeds skipped after `token` was cancelled are not recorded.
+fn search_with_checkpoint(
+    seeds: Vec<i32>,
+    game: &GameDesc,
+    rule: &(dyn Rule + Send + Sync),
+    writer: &Mutex<CheckpointWriter>,
+    token: &CancelToken,
+) -> Vec<i32> {
+    let known: Vec<Option<bool>> = {
+        let writer = writer.lock().unwrap();
+        let checkpoint = &writer.checkpoint;
+        seeds
+            .iter()
+            .map(|&seed| {
+                if checkpoint.is_completed(seed) {
+                    Some(checkpoint.find_match(seed).is_some())
+                } else {
+                    None
+                }
+            })
+            .collect()
+    };
+
+    let evaluated: Vec<(i32, Option<StarMask>)> = seeds
+        .into_iter()
+        .zip(known)
+        .filter_map(|(seed, known)| match known {
+            Some(true) => Some((seed, None)),
+            Some(false) => None,
+            None => (!token.is_cancelled()).then(|| {
+                token.record(1);
+                (seed, Some(find_stars(seed, game, rule)))
+            }),
+        })
+        .collect();
+
+    let mut fresh: Vec<(i32, StarMask)> = evaluated
+        .iter()
+        .filter_map(|&(seed, result)| result.map(|r| (seed, r)))
+        .collect();
+    fresh.sort_unstable_by_key(|&(seed, _)| seed);
+
+    let mut writer = writer.lock().unwrap();
+    let mut index = 0;
+    while index < fresh.len() {
+        let run_start = fresh[index].0;
+        let mut run_end = run_start + 1;
+        let mut matches = vec![];
+        while index < fresh.len() && fresh[index].0 < run_end + 1 {
+            let (seed, result) = fresh[index];
+            if !result.is_empty() {
+                matches.push(SeedMatch {
+                    seed,
+                    indexes: search::star_indexes(result),
+                });
+            }
+            run_end = seed + 1;
+            index += 1;
+        }
+        writer.checkpoint.record(run_start, run_end, matches);
+    }
+    if let Err(e) = writer.save_if_due() {
+        eprintln!("Warning: Failed to write checkpoint: {}", e);
+    }
+
+    evaluated
+        .into_iter()
+        .filter(|(_, result)| result.is_none_or(|r| !r.is_empty()))
+        .map(|(seed, _)| seed)
+        .collect()
+}
+
+/// Runs `to_message` for every seed on scheduler queue `queue` and sends each
+/// result as soon as it is ready, then a `BatchDone` for `id`, or a
+/// `BatchCancelled` if `token` was cancelled in between.
+pub fn run_batch<F>(
+    scheduler: &Arc<Scheduler>,
+    queue: u64,
+    id: u64,
+    seeds: Vec<i32>,
+    tx: &mpsc::Sender<Message>,
+    token: &CancelToken,
+    to_message: F,
+) where
+    F: Fn(i32) -> String + Send + Sync + 'static,
+{
+    let evaluate = {
+        let tx = tx.clone();
+        let token = token.clone();
+        move |seeds: Vec<i32>| {
+            for seed in seeds.into_iter().take_while(|_| !token.is_cancelled()) {
+                token.record(1);
+                let text = panic::catch_unwind(AssertUnwindSafe(|| to_message(seed)))
+                    .unwrap_or_else(|_| {
+                        let message = format!("Failed to process seed {}", seed);
+                        error_message(ErrorCode::Internal, message, None, Some(id))
+                    });
+                let _ = tx.blocking_send(Message::Text(text.into()));
+            }
+            vec![]
+        }
+    };
+    let tx = tx.clone();
+    let token = token.clone();
+    scheduler.submit_batch(queue, seeds, evaluate, move |_| {
+        let done = if token.is_cancelled() {
+            encode(&OutgoingMessage::BatchCancelled { id, session: None })
+        } else {
+            encode(&OutgoingMessage::BatchDone { id })
+        };
+        let _ = tx.blocking_send(Message::Text(done.into()));
+    });
+}
+
+/// A rule and game settings searched on the shared scheduler.
+struct Session {
+    game: GameDesc,
+    rule: Arc<Box<dyn Rule + Send + Sync>>,
+    checkpoint: Option<Arc<Mutex<CheckpointWriter>>>,
+    control: Arc<SearchControl>,
+    throughput: Throughput,
+    queue: u64,
+}
+
+enum SearchOutcome {
+    Matches(Vec<i32>),
+    Cancelled,
+    Failed,
+}
+
+impl Session {
+    /// Evaluates `seeds` on the scheduler, then calls `done` from the rayon
+    /// pool with the matches in input order.
+    fn search<F>(&self, scheduler: &Arc<Scheduler>, seeds: Vec<i32>, done: F)
+    where
+        F: FnOnce(SearchOutcome) + Send + 'static,
+    {
+        let game = self.game;
+        let rule = Arc::clone(&self.rule);
+        let checkpoint = self.checkpoint.clone();
+        let token = self.control.token();
+        let evaluate = {
+            let rule = Arc::clone(&rule);
+            let token = token.clone();
+            move |seeds: Vec<i32>| {
+                let rule = rule.as_ref().as_ref();
+                if let Some(writer) = &checkpoint {
+                    return search_with_checkpoint(seeds, &game, rule, writer, &token);
+                }
+                seeds
+                    .into_iter()
+                    .take_while(|_| !token.is_cancelled())
+                    .filter(|&seed| {
+                        token.record(1);
+                        !find_stars(seed, &game, rule).is_empty()
+                    })
+                    .collect()
+            }
+        };
+        scheduler.submit_batch(self.queue, seeds, evaluate, move |result| {
+            rule.adapt();
+            done(match result {
+                _ if token.is_cancelled() => SearchOutcome::Cancelled,
+                Some(matches) => SearchOutcome::Matches(matches),
+                None => SearchOutcome::Failed,
+            })
+        });
+    }
+
+    fn close(&self, scheduler: &Scheduler) {
+        self.control.cancel();
+        scheduler.unregister(self.queue);
+        if let Some(writer) = &self.checkpoint {
+            if let Err(e) = writer.lock().unwrap().save() {
+                eprintln!("Warning: Failed to write checkpoint: {}", e);
+            }
+        }
+    }
+}
+
+pub struct Connection {
+    scheduler: Arc<Scheduler>,
+    sessions: HashMap<String, Session>,
+    /// Session that binary batches go to, the one set up last.
+    current: Option<String>,
+    /// Controls `GenerateBatch` and `SearchStarBatch`.
+    control: Arc<SearchControl>,
+    throughput: Throughput,
+    /// Scheduler queue of `GenerateBatch` and `SearchStarBatch`.
+    queue: u64,
+}
+
+impl Connection {
+    pub fn new(scheduler: Arc<Scheduler>) -> Self {
+        let control = Arc::new(SearchControl::default());
+        let queue = scheduler.register(scheduler.get_threads(), Arc::clone(&control));
+        Self {
+            scheduler,
+            sessions: HashMap::new(),
+            current: None,
+            throughput: Throughput::new(&control),
+            control,
+            queue,
+        }
+    }
+
+    /// Closes `session` and lets the scheduler drain what it held back while
+    /// the session was paused.
+    fn close_session(&mut self, name: &str) {
+        if let Some(session) = self.sessions.remove(name) {
+            session.close(&self.scheduler);
+            self.scheduler.dispatch();
+        }
+    }
+
+    /// Applies `f` to the control of `session`, or to every control of the
+    /// connection, then lets the scheduler pick up jobs that were held back.
+    fn apply(&self, session: Option<&String>, f: fn(&SearchControl)) -> Result<(), String> {
+        match session {
+            Some(name) => match self.sessions.get(name) {
+                Some(session) => f(&session.control),
+                None => return Err(format!("No session named {}", name)),
+            },
+            None => {
+                f(&self.control);
+                for session in self.sessions.values() {
+                    f(&session.control);
+                }
+            }
+        }
+        self.scheduler.dispatch();
+        Ok(())
+    }
+
+    /// `Progress` of everything that evaluated seeds since the last call.
+    fn get_progress(&mut self) -> Vec<String> {
+        let mut messages = vec![];
+        if let Some((processed, seeds_per_second)) = self.throughput.tick(&self.control) {
+            messages.push(encode(&OutgoingMessage::Progress {
+                session: None,
+                processed,
+                seeds_per_second,
+                paused: self.control.is_paused(),
+            }));
+        }
+        for (name, session) in &mut self.sessions {
+            if let Some((processed, seeds_per_second)) = session.throughput.tick(&session.control) {
+                messages.push(encode(&OutgoingMessage::Progress {
+                    session: Some(name.clone()),
+                    processed,
+                    seeds_per_second,
+                    paused: session.control.is_paused(),
+                }));
+            }
+        }
+        messages
+    }
+
+    fn close(&mut self) {
+        self.control.cancel();
+        self.scheduler.unregister(self.queue);
+        for (_, session) in self.sessions.drain() {
+            session.close(&self.scheduler);
+        }
+        self.scheduler.dispatch();
+    }
+}
+
+/// Batches are answered from the rayon pool through `tx`, everything else
+/// returns its response.
+pub async fn handle_message(
+    msg: IncomingMessage,
+    connection: &mut Connection,
+    tx: &mpsc::Sender<Message>,
+) -> Option<String> {
+    let request = msg.get_type();
+    if let Err(e) = msg.validate() {
+        let error = error_message(ErrorCode::InvalidGame, e, Some(request), msg.get_id());
+        if let IncomingMessage::Setup { session, .. } = &msg {
+            connection.close_session(session);
+            let _ = tx.send(Message::Text(error.into())).await;
+            return Some(encode(&OutgoingMessage::Setup { success: false }));
+        }
+        return Some(error);
+    }
+    let response = match msg {
+        IncomingMessage::Hello { version } if version > PROTOCOL_VERSION => error_message(
+            ErrorCode::UnsupportedVersion,
+            format!(
+                "Protocol version {} is not supported, the server speaks up to {}",
+                version, PROTOCOL_VERSION
+            ),
+            Some(request),
+            None,
+        ),
+        IncomingMessage::Hello { .. } => encode(&OutgoingMessage::Hello {
+            version: PROTOCOL_VERSION,
+            server: env!("CARGO_PKG_VERSION"),
+            capabilities: CAPABILITIES,
+        }),
+        IncomingMessage::Generate {
+            seed,
+            game,
+            options,
+        } => {
+            run_blocking(request, move || {
+                let galaxy = crate::create_galaxy_with_options(seed, &game, options);
+                encode(&OutgoingMessage::Generate { galaxy })
+            })
+            .await
+        }
+        IncomingMessage::Setup {
+            session,
+            concurrency,
+            game,
+            rule,
+            checkpoint,
+            adaptive,
+        } => {
+            connection.close_session(&session);
+            let checkpoint = match checkpoint {
+                Some(path) => {
+                    match CheckpointWriter::open(
+                        PathBuf::from(path),
+                        &game,
+                        &rule,
+                        CHECKPOINT_INTERVAL,
+                    ) {
+                        Ok(writer) => Some(Arc::new(Mutex::new(writer))),
+                        Err(e) => {
+                            eprintln!("Warning: Failed to open checkpoint: {}", e);
+                            return Some(setup_failed(tx, e).await);
+                        }
+                    }
+                }
+                None => None,
+            };
+            let control = Arc::new(SearchControl::default());
+            let queue = connection
+                .scheduler
+                .register(concurrency, Arc::clone(&control));
+            connection.sessions.insert(
+                session.clone(),
+                Session {
+                    game,
+                    rule: Arc::new(if adaptive {
+                        transform_rules_adaptive(rule, &mut vec![], 0)
+                    } else {
+                        transform_rules(rule)
+                    }),
+                    checkpoint,
+                    throughput: Throughput::new(&control),
+                    control,
+                    queue,
+                },
+            );
+            connection.current = Some(session);
+            encode(&OutgoingMessage::Setup { success: true })
+        }
+        IncomingMessage::SearchStar { seed, game, rule } => {
+            run_blocking(request, move || {
+                let transformed_rule = transform_rules(rule);
+                let star_indexes = find_stars(seed, &game, transformed_rule.as_ref());
+                encode(&OutgoingMessage::SearchStar {
+                    indexes: search::star_indexes(star_indexes),
+                })
+            })
+            .await
+        }
+        IncomingMessage::Explain { seed, game, rule } => {
+            run_blocking(request, move || {
+                let transformed_rule = transform_rules(rule);
+                let explanation = explain_stars(seed, &game, transformed_rule.as_ref());
+                encode(&OutgoingMessage::Explain { explanation })
+            })
+            .await
+        }
+        IncomingMessage::Diff {
+            from_seed,
+            from_game,
+            to_seed,
+            to_game,
+        } => {
+            run_blocking(request, move || {
+                let diff = diff_seeds(from_seed, &from_game, to_seed, &to_game);
+                encode(&OutgoingMessage::Diff { diff })
+            })
+            .await
+        }
+        IncomingMessage::GenerateBatch {
+            id,
+            seeds,
+            game,
+            options,
+        } => {
+            let token = connection.control.token();
+            run_batch(
+                &connection.scheduler,
+                connection.queue,
+                id,
+                seeds,
+                tx,
+                &token,
+                move |seed| {
+                    let galaxy = crate::create_galaxy_with_options(seed, &game, options);
+                    encode(&OutgoingMessage::GenerateResult { id, seed, galaxy })
+                },
+            );
+            return None;
+        }
+        IncomingMessage::SearchStarBatch {
+            id,
+            seeds,
+            game,
+            rule,
+        } => {
+            let token = connection.control.token();
+            let rule = transform_rules(rule);
+            run_batch(
+                &connection.scheduler,
+                connection.queue,
+                id,
+                seeds,
+                tx,
+                &token,
+                move |seed| {
+                    let star_indexes = find_stars(seed, &game, rule.as_ref());
+                    encode(&OutgoingMessage::SearchStarResult {
+                        id,
+                        seed,
+                        indexes: search::star_indexes(star_indexes),
+                    })
+                },
+            );
+            return None;
+        }
+        IncomingMessage::Search {
+            session: name,
+            id,
+            seeds,
+        } => {
+            let Some(session) = connection.sessions.get(&name) else {
+                let message = format!("No session named {}", name);
+                return Some(error_message(
+                    ErrorCode::NotSetUp,
+                    message,
+                    Some(request),
+                    Some(id),
+                ));
+            };
+            let tx = tx.clone();
+            session.search(&connection.scheduler, seeds, move |outcome| {
+                let text = match outcome {
+                    SearchOutcome::Matches(seeds) => encode(&OutgoingMessage::SearchResult {
+                        session: name,
+                        id,
+                        seeds,
+                    }),
+                    SearchOutcome::Cancelled => encode(&OutgoingMessage::BatchCancelled {
+                        id,
+                        session: Some(name),
+                    }),
+                    SearchOutcome::Failed => {
+                        let message = "Failed to search the batch";
+                        error_message(ErrorCode::Internal, message, Some("Search"), Some(id))
+                    }
+                };
+                let _ = tx.blocking_send(Message::Text(text.into()));
+            });
+            return None;
+        }
+        IncomingMessage::Close { session } => {
+            connection.close_session(&session);
+            encode(&OutgoingMessage::Close)
+        }
+        IncomingMessage::Cancel { session } => {
+            match connection.apply(session.as_ref(), SearchControl::cancel) {
+                Ok(()) => encode(&OutgoingMessage::Cancel),
+                Err(e) => error_message(ErrorCode::NotSetUp, e, Some(request), None),
+            }
+        }
+        IncomingMessage::Pause { session } => {
+            match connection.apply(session.as_ref(), SearchControl::pause) {
+                Ok(()) => encode(&OutgoingMessage::Pause),
+                Err(e) => error_message(ErrorCode::NotSetUp, e, Some(request), None),
+            }
+        }
+        IncomingMessage::Resume { session } => {
+            match connection.apply(session.as_ref(), SearchControl::resume) {
+                Ok(()) => encode(&OutgoingMessage::Resume),
+                Err(e) => error_message(ErrorCode::NotSetUp, e, Some(request), None),
+            }
+        }
+    };
+    Some(response)
+}
+
+/// Reports why `Setup` failed.
+async fn setup_failed(tx: &mpsc::Sender<Message>, e: impl ToString) -> String {
+    let error = error_message(ErrorCode::SetupFailed, e, Some("Setup"), None);
+    let _ = tx.send(Message::Text(error.into())).await;
+    encode(&OutgoingMessage::Setup { success: false })
+}
+
+async fn accept_connection(
+    stream: TcpStream,
+    scheduler: Arc<Scheduler>,
+) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+    let ws_stream = match accept_async(stream).await {
+        Ok(ws_stream) => ws_stream,
+        Err(e) => {
+            eprintln!("Warning: Error during websocket handshake: {}", e);
+            return Err(e);
+        }
+    };
+    let (mut write, mut read) = ws_stream.split();
+
+    let mut connection = Connection::new(scheduler);
+    let (tx, mut rx) = mpsc::channel::<Message>(1000);
+
+    tokio::spawn(async move {
+        while let Some(msg) = rx.recv().await {
+            let _ = write.send(msg).await;
+        }
+    });
+
+    let mut progress = tokio::time::interval(PROGRESS_INTERVAL);
+    let mut outcome = Ok(());
+    loop {
+        let result = tokio::select! {
+            result = read.next() => result,
+            _ = progress.tick() => {
+                for text in connection.get_progress() {
+                    let _ = tx.send(Message::Text(text.into())).await;
+                }
+                continue;
+            }
+        };
+        let Some(result) = result else {
+            break;
+        };
+        let msg = match result {
+            Ok(msg) => msg,
+            Err(e) => {
+                outcome = Err(e);
+                break;
+            }
+        };
+        match msg {
+            Message::Text(text) => {
+                let resp = match serde_json::from_str::<IncomingMessage>(&text) {
+                    Ok(msg) => handle_message(msg, &mut connection, &tx).await,
+                    Err(e) => Some(error_message(ErrorCode::InvalidMessage, e, None, None)),
+                };
+                if let Some(resp) = resp {
+                    let _ = tx.send(Message::Text(resp.into())).await;
+                }
+            }
+            Message::Binary(bytes) => {
+                // println!("Receive search request for {} seeds", size);
+                if bytes.len() < 8 || bytes.len() % 4 != 0 {
+                    eprintln!("Warning: Received malformed binary packet.");
+                    let message =
+                        format!("Expected a batch id and seeds, got {} bytes", bytes.len());
+                    let error = error_message(ErrorCode::MalformedBinary, message, None, None);
+                    let _ = tx.send(Message::Text(error.into())).await;
+                    continue;
+                }
+                let id = u32::from_ne_bytes(bytes[..4].try_into().unwrap());
+                let current = connection.current.as_ref().and_then(|name| {
+                    let session = connection.sessions.get(name)?;
+                    Some((name.clone(), session))
+                });
+                let Some((name, session)) = current else {
+                    eprintln!("Warning: Received search request before setup.");
+                    let message = "Search requested before a successful Setup";
+                    let error = error_message(ErrorCode::NotSetUp, message, None, Some(id as u64));
+                    let _ = tx.send(Message::Text(error.into())).await;
+                    continue;
+                };
+                let seeds: Vec<i32> = bytes[4..]
+                    .chunks_exact(4)
+                    .map(|chunk| i32::from_ne_bytes(chunk.try_into().unwrap()))
+                    .collect();
+                let tx = tx.clone();
+                session.search(&connection.scheduler, seeds, move |outcome| {
+                    let msg = match outcome {
+                        SearchOutcome::Matches(seeds) => {
+                            let mut result = id.to_ne_bytes().to_vec();
+                            for seed in seeds {
+                                result.extend_from_slice(&seed.to_ne_bytes());
+                            }
+                            Message::Binary(result.into())
+                        }
+                        SearchOutcome::Cancelled => {
+                            let text = encode(&OutgoingMessage::BatchCancelled {
+                                id: id as u64,
+                                session: Some(name),
+                            });
+                            Message::Text(text.into())
+                        }
+                        SearchOutcome::Failed => {
+                            let message = "Failed to search the batch";
+                            let id = Some(id as u64);
+                            Message::Text(
+                                error_message(ErrorCode::Internal, message, None, id).into(),
+                            )
+                        }
+                    };
+                    let _ = tx.blocking_send(msg);
+                });
+            }
+            _ => {}
+        }
+    }
+
+    connection.close();
+
+    outcome
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::bitset::StarMask;
+    use std::cell::Cell;
+
+    use crate::data::game_desc::GameDesc;
+    use crate::data::rule::Condition;
+    use crate::scores::star_value::StarValue;
+    use crate::scores::stars::Aggregate;
+    use crate::transform_rules::{transform_rules, Rules};
+    use crate::transform_scores::Scores;
+    use crate::worldgen::galaxy_gen::{create_galaxy, find_stars};
+
+    fn total_rule(total: f32) -> Rules {
+        Rules::Aggregate {
+            score: Box::new(Scores::Stars {
+                aggregate: Aggregate::Sum,
+                value: StarValue::TidalLockCount,
+                filter: None,
+            }),
+            condition: Condition::Eq(total),
+        }
+    }
+
+    #[test]
+    fn test_aggregate_tidal_lock_total() {
+        let game = GameDesc {
+            star_count: 32,
+            ..Default::default()
+        };
+        let habitable_count = Cell::new(0_i32);
+        let galaxy = create_galaxy(7, &game, &habitable_count);
+        let total: usize = galaxy
+            .stars
+            .iter()
+            .map(|sp| {
+                sp.get_planets()
+                    .iter()
+                    .filter(|planet| planet.is_tidal_locked())
+                    .count()
+            })
+            .sum();
+
+        let matching = transform_rules(total_rule(total as f32));
+        assert_eq!(find_stars(7, &game, matching.as_ref()), StarMask::first(32));
+        let other = transform_rules(total_rule(total as f32 + 1.0));
+        assert!(find_stars(7, &game, other.as_ref()).is_empty());
+    }
+}
@@ -0,0 +1,80 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::game_desc::GameDesc;
+    use crate::search::star_indexes;
+    use crate::transform_rules::{self, Rules};
+    use crate::transform_scores::{self, Scores};
+    use crate::view::ViewOptions;
+    use crate::worldgen::galaxy_gen;
+    use std::cell::Cell;
+
+    fn game(use_actual_veins: bool) -> GameDesc {
+        GameDesc {
+            star_count: 16,
+            use_actual_veins,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_galaxy_view_matches_generate() {
+        for game in [game(false), game(true)] {
+            let habitable_count = Cell::new(0_i32);
+            let galaxy = galaxy_gen::create_galaxy(7, &game, &habitable_count);
+            let view = crate::create_galaxy(7, &game);
+            assert_eq!(view.stars.len(), 16);
+            assert_eq!(
+                serde_json::to_value(&view).unwrap(),
+                serde_json::to_value(&galaxy).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_vein_groups_option() {
+        let game = game(true);
+        let view = crate::create_galaxy(7, &game);
+        assert!(view.stars[0].planets[0].vein_groups.is_none());
+        let options = ViewOptions { vein_groups: true };
+        let view = crate::create_galaxy_with_options(7, &game, options);
+        assert!(view.stars[0].planets[0].vein_groups.is_some());
+    }
+
+    #[test]
+    fn test_find_stars() {
+        let game = game(false);
+        let rule: Rules = serde_json::from_value(serde_json::json!({
+            "type": "Luminosity",
+            "condition": { "type": "Gte", "value": 1.0 },
+        }))
+        .unwrap();
+        let internal = transform_rules::transform_rules(rule.clone());
+        let compiled = crate::transform_rules(rule);
+        for seed in 0..20 {
+            let indexes = crate::find_stars(seed, &game, &compiled);
+            let expected = galaxy_gen::find_stars(seed, &game, internal.as_ref());
+            assert_eq!(indexes, star_indexes(expected));
+            let galaxy = crate::create_galaxy(seed, &game);
+            for index in indexes {
+                assert!(galaxy.stars[index].luminosity >= 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_score_galaxy() {
+        let game = game(false);
+        let score: Scores = serde_json::from_value(serde_json::json!({
+            "type": "Stars",
+            "aggregate": "Sum",
+            "value": { "type": "Luminosity" },
+        }))
+        .unwrap();
+        let internal = transform_scores::transform_scores(score.clone());
+        let compiled = crate::transform_scores(score);
+        for seed in 0..5 {
+            let expected = galaxy_gen::score_galaxy(seed, &game, None, internal.as_ref());
+            assert_eq!(Some(crate::score_galaxy(seed, &game, &compiled)), expected);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(test)]
+mod tests {
+    use crate::control::SearchControl;
+    use crate::scheduler::Scheduler;
+    use crate::server::run_batch;
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+
+    /// Every text sent for a batch, up to and including its last message.
+    fn receive(rx: &mut mpsc::Receiver<Message>) -> Vec<String> {
+        let mut texts = vec![];
+        while let Some(Message::Text(text)) = rx.blocking_recv() {
+            let text = text.to_string();
+            let last = text.contains("BatchDone") || text.contains("BatchCancelled");
+            texts.push(text);
+            if last {
+                break;
+            }
+        }
+        texts
+    }
+
+    #[test]
+    fn test_batch_streams_every_seed_then_done() {
+        let (tx, mut rx) = mpsc::channel::<Message>(100);
+        let scheduler = Scheduler::new(2);
+        let control = Arc::new(SearchControl::default());
+        let queue = scheduler.register(2, Arc::clone(&control));
+        run_batch(
+            &scheduler,
+            queue,
+            7,
+            (0..200).collect(),
+            &tx,
+            &control.token(),
+            |seed| seed.to_string(),
+        );
+
+        let mut texts = receive(&mut rx);
+        assert_eq!(texts.len(), 201);
+        assert_eq!(texts.pop().unwrap(), r#"{"type":"BatchDone","id":7}"#);
+        let mut seeds: Vec<i32> = texts.iter().map(|text| text.parse().unwrap()).collect();
+        seeds.sort_unstable();
+        assert_eq!(seeds, (0..200).collect::<Vec<_>>());
+        assert_eq!(control.get_processed(), 200);
+
+        run_batch(
+            &scheduler,
+            queue,
+            9,
+            vec![],
+            &tx,
+            &control.token(),
+            |seed| seed.to_string(),
+        );
+        assert_eq!(receive(&mut rx), [r#"{"type":"BatchDone","id":9}"#]);
+    }
+
+    #[test]
+    fn test_cancelled_batch_stops_early() {
+        let (tx, mut rx) = mpsc::channel::<Message>(10_001);
+        let scheduler = Scheduler::new(1);
+        let control = Arc::new(SearchControl::default());
+        let queue = scheduler.register(1, Arc::clone(&control));
+        let token = control.token();
+        run_batch(&scheduler, queue, 8, (0..10_000).collect(), &tx, &token, {
+            let control = Arc::clone(&control);
+            move |seed| {
+                if seed == 0 {
+                    control.cancel();
+                }
+                seed.to_string()
+            }
+        });
+
+        let mut texts = receive(&mut rx);
+        assert!(texts.len() < 10_001);
+        assert_eq!(texts.pop().unwrap(), r#"{"type":"BatchCancelled","id":8}"#);
+        // Batches started after the cancellation are not affected.
+        assert!(!control.token().is_cancelled());
+    }
+
+    #[test]
+    fn test_paused_batch_waits_in_queue() {
+        let (tx, mut rx) = mpsc::channel::<Message>(100);
+        let scheduler = Scheduler::new(1);
+        let control = Arc::new(SearchControl::default());
+        let queue = scheduler.register(1, Arc::clone(&control));
+        control.pause();
+        run_batch(
+            &scheduler,
+            queue,
+            4,
+            vec![1, 2],
+            &tx,
+            &control.token(),
+            |seed| seed.to_string(),
+        );
+        thread::sleep(Duration::from_millis(50));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(control.get_processed(), 0);
+
+        // The pool is free for other queues in the meantime.
+        let other_control = Arc::new(SearchControl::default());
+        let other = scheduler.register(1, Arc::clone(&other_control));
+        run_batch(
+            &scheduler,
+            other,
+            5,
+            vec![3],
+            &tx,
+            &other_control.token(),
+            |seed| seed.to_string(),
+        );
+        assert_eq!(receive(&mut rx), ["3", r#"{"type":"BatchDone","id":5}"#]);
+
+        control.resume();
+        scheduler.dispatch();
+        assert_eq!(receive(&mut rx).len(), 3);
+
+        control.pause();
+        run_batch(
+            &scheduler,
+            queue,
+            6,
+            vec![1],
+            &tx,
+            &control.token(),
+            |seed| seed.to_string(),
+        );
+        control.cancel();
+        scheduler.dispatch();
+        assert_eq!(receive(&mut rx), [r#"{"type":"BatchCancelled","id":6}"#]);
+        assert!(!control.is_paused());
+    }
+}
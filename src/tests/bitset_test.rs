@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::bitset::{Bitset, StarMask};
+    use crate::data::game_desc::GameDesc;
+    use crate::data::rule::Evaluation;
+    use crate::transform_rules::transform_rules;
+    use crate::worldgen::galaxy_gen::{create_galaxy, find_stars};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_bitset() {
+        let set: Bitset<3> = [0, 63, 64, 130].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![0, 63, 64, 130]);
+        assert_eq!(set.count_ones(), 4);
+        assert_eq!(set.get_end(), 131);
+        assert!(set.contains(64) && !set.contains(65));
+        assert_eq!(Bitset::<3>::first(70).count_ones(), 70);
+        assert_eq!(Bitset::<3>::first(192), !Bitset::new());
+        assert_eq!((set & Bitset::first(64)).get_end(), 64);
+        assert_eq!(
+            serde_json::to_value(set).unwrap(),
+            serde_json::json!([0, 63, 64, 130])
+        );
+
+        let mut evaluation = Evaluation::<3>::new(150);
+        evaluation.reject_others(set);
+        assert_eq!(evaluation.get_len(), 131);
+        evaluation.accept_many(Bitset::first(100));
+        assert_eq!(evaluation.collect_known(), Bitset::first(100) & set);
+        assert_eq!(evaluation.get_len(), 131);
+        assert_eq!(evaluation.collect_unknown(), set);
+    }
+
+    #[test]
+    fn test_more_than_64_stars() {
+        let game = GameDesc {
+            star_count: 200,
+            ..Default::default()
+        };
+        let rule = serde_json::from_value(serde_json::json!({
+            "type": "And",
+            "rules": [
+                { "type": "Luminosity", "condition": { "type": "Gte", "value": 1.0 } },
+                { "type": "PlanetCount", "condition": { "type": "Gte", "value": 3 } },
+            ],
+        }))
+        .unwrap();
+        let rule = transform_rules(rule);
+        let habitable_count = Cell::new(0_i32);
+        let galaxy = create_galaxy(3, &game, &habitable_count);
+        assert!(galaxy.stars.len() > 64);
+        let expected: StarMask = galaxy
+            .stars
+            .iter()
+            .filter(|sp| sp.star.get_luminosity() >= 1.0 && sp.get_planets().len() >= 3)
+            .map(|sp| sp.star.index)
+            .collect();
+        assert!(expected.get_end() > 64);
+        assert_eq!(find_stars(3, &game, rule.as_ref()), expected);
+    }
+}
@@ -0,0 +1,29 @@
+#[cfg(test)]
+mod tests {
+    use crate::checkpoint::Checkpoint;
+    use crate::data::game_desc::GameDesc;
+    use crate::search::SeedMatch;
+
+    #[test]
+    fn test_checkpoint_ranges() {
+        let game = GameDesc::default();
+        let mut checkpoint = Checkpoint::new(game, 0);
+        checkpoint.record(20, 30, vec![]);
+        checkpoint.record(
+            0,
+            10,
+            vec![SeedMatch {
+                seed: 5,
+                indexes: vec![1],
+            }],
+        );
+        assert_eq!(checkpoint.remaining(0, 40), vec![(10, 20), (30, 40)]);
+
+        checkpoint.record(10, 20, vec![]);
+        assert_eq!(checkpoint.completed, vec![(0, 30)]);
+        assert_eq!(checkpoint.remaining(0, 40), vec![(30, 40)]);
+        assert!(checkpoint.is_completed(29));
+        assert!(!checkpoint.is_completed(30));
+        assert!(checkpoint.find_match(5).is_some());
+    }
+}
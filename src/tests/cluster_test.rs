@@ -0,0 +1,96 @@
+#[cfg(test)]
+mod tests {
+    use crate::cluster::{coordinate, work, LeaseTable};
+    use crate::data::game_desc::GameDesc;
+    use crate::search::{star_indexes, SearchConfig, SeedMatch};
+    use crate::transform_rules::transform_rules;
+    use crate::worldgen::galaxy_gen::find_stars;
+    use rayon::ThreadPoolBuilder;
+    use std::time::{Duration, Instant};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_lease_table_reissues_expired_leases() {
+        let timeout = Duration::from_secs(10);
+        let mut table = LeaseTable::new(&[(0, 25), (40, 50)], 10, timeout);
+        assert_eq!(table.len(), 4);
+        let now = Instant::now();
+        let first = table.issue(1, now).unwrap();
+        assert_eq!((first.start, first.end), (0, 10));
+        let second = table.issue(2, now).unwrap();
+        assert_eq!((second.start, second.end), (10, 20));
+
+        // Worker 1 keeps its lease alive, worker 2 goes silent.
+        let later = now + Duration::from_secs(8);
+        assert!(table.renew(first.id, 1, later));
+        assert!(!table.renew(second.id, 1, later));
+        let expired = now + Duration::from_secs(12);
+        let reissued = table.issue(3, expired).unwrap();
+        assert_eq!(reissued, second);
+
+        // The first report wins, the late one is ignored.
+        assert_eq!(table.complete(second.id), Some((10, 20)));
+        assert_eq!(table.complete(second.id), None);
+
+        table.release(1, expired);
+        assert_eq!(table.issue(3, expired), Some(first));
+        assert_eq!(table.issue(3, expired).unwrap().end, 25);
+        assert_eq!(table.issue(3, expired).unwrap().start, 40);
+        assert_eq!(table.issue(3, expired), None);
+        assert_eq!(table.len(), 3);
+    }
+
+    #[test]
+    fn test_workers_on_localhost() {
+        let game = GameDesc {
+            star_count: 32,
+            ..Default::default()
+        };
+        let rule = serde_json::from_value(serde_json::json!({
+            "type": "Luminosity",
+            "condition": { "type": "Gte", "value": 1.5 },
+        }))
+        .unwrap();
+        let config = SearchConfig { game, rule };
+        let transformed = transform_rules(config.rule.clone());
+        let expected: Vec<SeedMatch> = (0..60)
+            .filter_map(|seed| {
+                let result = find_stars(seed, &game, transformed.as_ref());
+                (!result.is_empty()).then(|| SeedMatch {
+                    seed,
+                    indexes: star_indexes(result),
+                })
+            })
+            .collect();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let mut found = runtime.block_on(async {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("ws://{}", listener.local_addr().unwrap());
+            let leases = LeaseTable::new(&[(0, 60)], 7, Duration::from_secs(30));
+            let workers: Vec<_> = (0..2)
+                .map(|_| {
+                    let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+                    tokio::spawn({
+                        let url = url.clone();
+                        async move { work(&url, pool).await }
+                    })
+                })
+                .collect();
+            let mut found = vec![];
+            coordinate(listener, &config, leases, |report| {
+                found.extend(report.matches);
+                Ok(())
+            })
+            .await
+            .unwrap();
+            for worker in workers {
+                worker.await.unwrap().unwrap();
+            }
+            found
+        });
+        assert!(!expected.is_empty());
+        found.sort_by_key(|m| m.seed);
+        assert_eq!(found, expected);
+    }
+}
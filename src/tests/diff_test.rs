@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::game_desc::GameDesc;
+    use crate::worldgen::galaxy_diff::diff_seeds;
+
+    #[test]
+    fn test_diff() {
+        let game = GameDesc {
+            star_count: 16,
+            ..Default::default()
+        };
+        assert!(diff_seeds(1, &game, 1, &game).stars.is_empty());
+
+        let dense = GameDesc {
+            hive_max_density: 3.0,
+            ..game
+        };
+        let diff = diff_seeds(1, &game, 1, &dense);
+        assert!(!diff.stars.is_empty());
+        for star in &diff.stars {
+            assert!(star.max_hive_count.is_some());
+            assert!(star.name.is_none() && star.spectr.is_none() && star.planets.is_empty());
+        }
+
+        let rare = GameDesc {
+            resource_multiplier: 0.1,
+            use_actual_veins: true,
+            ..game
+        };
+        let diff = diff_seeds(1, &game, 1, &rare);
+        let vein = &diff.stars[0].planets[0].veins[0];
+        assert!(vein.estimated.as_ref().is_some_and(|c| c.to < c.from));
+        assert!(vein.actual.as_ref().is_some_and(|c| c.to < c.from));
+
+        let diff = diff_seeds(1, &game, 2, &game);
+        assert!(diff.stars.iter().any(|star| star.name.is_some()));
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["seed"]["to"], 2);
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::enums::SpectrType;
+    use crate::data::game_desc::GameDesc;
+    use crate::data::rule::{Condition, Evaluation, Rule};
+    use crate::rules::hop_count::RuleHopCount;
+    use crate::rules::luminosity::RuleLuminosity;
+    use crate::rules::planet_count::RulePlanetCount;
+    use crate::rules::spectr_distance::RuleSpectrDistance;
+    use crate::rules::x_distance::RuleXDistance;
+    use crate::transform_rules::{transform_rules, Rules};
+    use crate::worldgen::galaxy_gen::{create_galaxy, explain_stars, find_stars};
+    use std::cell::Cell;
+
+    #[test]
+    fn test_explain_matches_search() {
+        let game = GameDesc::default();
+        let rule = transform_rules(Rules::And {
+            rules: vec![
+                Rules::Luminosity(RuleLuminosity {
+                    condition: Condition::Gte(1.0),
+                }),
+                Rules::PlanetCount(RulePlanetCount {
+                    exclude_giant: false,
+                    condition: Condition::Gte(4.0),
+                }),
+            ],
+        });
+        for seed in 0..20 {
+            let explanation = explain_stars(seed, &game, rule.as_ref());
+            assert_eq!(explanation.result, find_stars(seed, &game, rule.as_ref()));
+            assert_eq!(explanation.rule, "And");
+            let first = &explanation.children[0];
+            assert_eq!(first.stars.len(), 64);
+            for star in &first.stars {
+                assert_eq!(star.passed, star.value.unwrap() >= 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain_x_distance() {
+        let game = GameDesc::default();
+        for all in [false, true] {
+            let rule = transform_rules(Rules::XDistance(RuleXDistance {
+                condition: Condition::Lte(30.0),
+                all,
+            }));
+            for seed in 0..10 {
+                let explanation = explain_stars(seed, &game, rule.as_ref());
+                for star in &explanation.stars {
+                    if let Some(value) = star.value {
+                        assert_eq!(star.passed, value <= 30.0);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain_galaxy_wide_rules() {
+        let game = GameDesc::default();
+        let hop_count = RuleHopCount {
+            max_hop: 8.0,
+            condition: Condition::Lte(3.0),
+        };
+        let spectr_distance = RuleSpectrDistance {
+            spectr: SpectrType::O,
+            distance_condition: Condition::Lte(20.0),
+            count_condition: Condition::Gte(1.0),
+        };
+        for rule in [
+            &hop_count as &(dyn Rule + Send + Sync),
+            &spectr_distance as &(dyn Rule + Send + Sync),
+        ] {
+            let habitable_count = Cell::new(0_i32);
+            let galaxy = create_galaxy(3, &game, &habitable_count);
+            let explanation = rule.explain(&galaxy, &Evaluation::new(galaxy.stars.len()));
+            assert_eq!(explanation.stars.len(), galaxy.stars.len());
+            for star in &explanation.stars {
+                let value = rule.measure(&galaxy, &galaxy.stars[star.index]);
+                assert_eq!(star.value, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::fs::File;
+
+    use crate::data::game_desc::GameDesc;
+    use crate::export::export_range;
+    use crate::worldgen::galaxy_gen::create_galaxy;
+    use arrow_ipc::reader::FileReader;
+    use rayon::ThreadPoolBuilder;
+
+    fn count_rows(path: &std::path::Path) -> usize {
+        let reader = FileReader::try_new(File::open(path).unwrap(), None).unwrap();
+        assert_eq!(reader.schema().metadata()["dsp_seed.version"], "1");
+        reader.map(|batch| batch.unwrap().num_rows()).sum()
+    }
+
+    #[test]
+    fn test_export() {
+        let game = GameDesc {
+            star_count: 32,
+            ..Default::default()
+        };
+        let dir = std::env::temp_dir().join(format!("dsp_seed_export_{}", std::process::id()));
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        export_range(&pool, &game, None, 10, 13, &dir, |_, _| {}).unwrap();
+
+        let mut planets = 0;
+        for seed in 10..13 {
+            let habitable_count = Cell::new(0_i32);
+            let galaxy = create_galaxy(seed, &game, &habitable_count);
+            planets += galaxy
+                .stars
+                .iter()
+                .map(|sp| sp.get_planets().len())
+                .sum::<usize>();
+        }
+        assert_eq!(count_rows(&dir.join("stars.arrow")), 3 * 32);
+        assert_eq!(count_rows(&dir.join("planets.arrow")), planets);
+        assert!(count_rows(&dir.join("veins.arrow")) > 0);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use crate::data::game_desc::GameDesc;
+    use crate::data::heightmap::Heightmap;
+    use crate::heightmap::{write_heightmap, HeightmapFormat, HeightmapReport};
+    use crate::transform_rules::transform_rules;
+    use crate::worldgen::galaxy_gen::{create_galaxy, find_stars};
+
+    #[test]
+    fn test_heightmap() {
+        let game = GameDesc::default();
+        let habitable_count = Cell::new(0_i32);
+        let galaxy = create_galaxy(1, &game, &habitable_count);
+        let planets = galaxy.stars[0].get_planets();
+        assert!(Heightmap::new(&planets[2], 64).is_none());
+
+        // An ocean world: part land, part sea.
+        let heightmap = Heightmap::new(&planets[3], 64).unwrap();
+        assert_eq!(heightmap.heights.len(), 64 * 32);
+        assert!(heightmap.get_min() < 0.0 && heightmap.get_max() > 0.0);
+        let report = HeightmapReport::new(1, 0, &planets[3], &heightmap);
+        assert_eq!(
+            report.land_fraction,
+            planets[3].get_land_fraction(true, 90.0)
+        );
+        assert!(0.0 < report.land_fraction && report.land_fraction < 1.0);
+        assert_eq!(report.ocean_fraction, 1.0 - report.land_fraction);
+
+        let path =
+            std::env::temp_dir().join(format!("dsp_seed_heightmap_{}.png", std::process::id()));
+        write_heightmap(&heightmap, HeightmapFormat::Png, &path).unwrap();
+        let png = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(heightmap.to_raw().len(), 64 * 32 * 4);
+    }
+
+    #[test]
+    fn test_land_fraction() {
+        let game = GameDesc::default();
+        let habitable_count = Cell::new(0_i32);
+        let galaxy = create_galaxy(1, &game, &habitable_count);
+        let planets = galaxy.stars[0].get_planets();
+        assert_eq!(planets[1].get_land_fraction(false, 90.0), 1.0);
+        assert_eq!(planets[2].get_land_fraction(false, 90.0), 0.0);
+
+        let exact = planets[3].get_land_fraction(true, 90.0);
+        let sampled = planets[3].get_land_fraction(false, 90.0);
+        assert!((exact - sampled).abs() < 0.005);
+        let belt = planets[3].get_land_fraction(true, 10.0);
+        assert!((0.0..=1.0).contains(&belt) && belt != exact);
+
+        let rule = transform_rules(
+            serde_json::from_str(&format!(
+                r#"{{
+                    "type": "Planet",
+                    "rule": {{
+                        "type": "LandFraction",
+                        "condition": {{ "type": "Between", "value": [{}, {}] }}
+                    }},
+                    "condition": {{ "type": "Gte", "value": 1 }}
+                }}"#,
+                sampled - 0.001,
+                sampled + 0.001
+            ))
+            .unwrap(),
+        );
+        assert!(find_stars(1, &game, rule.as_ref()).contains(0));
+    }
+}
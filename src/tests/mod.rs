@@ -1 +1,20 @@
+pub mod aggregate_test;
+pub mod api_test;
+pub mod batch_test;
+pub mod bitset_test;
+pub mod checkpoint_test;
+pub mod cluster_test;
+pub mod diff_test;
+pub mod explain_test;
+pub mod export_test;
+pub mod heightmap_test;
+pub mod parity_test;
+pub mod planet_rule_test;
+pub mod profile_test;
+pub mod protocol_test;
+pub mod scheduler_test;
+pub mod search_test;
+pub mod star_graph_test;
+pub mod theme_proto_test;
+pub mod vein_test;
 pub mod worldgen_test;
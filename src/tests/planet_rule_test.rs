@@ -0,0 +1,90 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::bitset::StarMask;
+    use crate::data::enums::Resonance;
+    use std::cell::Cell;
+
+    use crate::data::game_desc::GameDesc;
+    use crate::transform_rules::{transform_rules, Rules};
+    use crate::worldgen::galaxy_gen::{create_galaxy, find_stars};
+
+    #[test]
+    fn test_planet_and() {
+        let game = GameDesc::default();
+        let rule: Rules = serde_json::from_str(
+            r#"{
+                "type": "Planet",
+                "rule": {
+                    "type": "And",
+                    "rules": [{ "type": "TidalLock" }, { "type": "Satellite" }]
+                },
+                "condition": { "type": "Gte", "value": 1 }
+            }"#,
+        )
+        .unwrap();
+        let rule = transform_rules(rule);
+        for seed in 0..10 {
+            let habitable_count = Cell::new(0_i32);
+            let galaxy = create_galaxy(seed, &game, &habitable_count);
+            let mut expected = StarMask::new();
+            for (index, sp) in galaxy.stars.iter().enumerate() {
+                if sp
+                    .get_planets()
+                    .iter()
+                    .any(|planet| planet.is_tidal_locked() && planet.has_orbit_around())
+                {
+                    expected.insert(index);
+                }
+            }
+            assert_eq!(find_stars(seed, &game, rule.as_ref()), expected);
+        }
+    }
+
+    #[test]
+    fn test_resonance_matches_tidal_lock() {
+        let game = GameDesc::default();
+        let parse = |json: &str| transform_rules(serde_json::from_str(json).unwrap());
+        let resonance = parse(
+            r#"{
+                "type": "Planet",
+                "rule": { "type": "Resonance", "resonances": ["TidalLock"] },
+                "condition": { "type": "Gte", "value": 1 }
+            }"#,
+        );
+        let tidal_lock =
+            parse(r#"{ "type": "TidalLockCount", "condition": { "type": "Gte", "value": 1 } }"#);
+        for seed in 0..10 {
+            assert_eq!(
+                find_stars(seed, &game, resonance.as_ref()),
+                find_stars(seed, &game, tidal_lock.as_ref())
+            );
+        }
+    }
+
+    #[test]
+    fn test_resonance_sets_rotation_period() {
+        let game = GameDesc::default();
+        let mut counts = [0; 3];
+        for seed in 0..5 {
+            let habitable_count = Cell::new(0_i32);
+            let galaxy = create_galaxy(seed, &game, &habitable_count);
+            for sp in &galaxy.stars {
+                for planet in sp.get_planets() {
+                    let (kind, ratio) = match planet.get_resonance() {
+                        Some(Resonance::TidalLock) => (0, 1.0),
+                        Some(Resonance::Half) => (1, 0.5),
+                        Some(Resonance::Quarter) => (2, 0.25),
+                        None => continue,
+                    };
+                    counts[kind] += 1;
+                    assert!(planet.get_eligible_for_resonance());
+                    assert_eq!(
+                        planet.get_rotation_period(),
+                        planet.get_orbital_period() * ratio
+                    );
+                }
+            }
+        }
+        assert!(counts.iter().all(|&count| count > 0));
+    }
+}
@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::game_desc::GameDesc;
+    use crate::profile::transform_rules_adaptive;
+    use crate::transform_rules::transform_rules;
+    use crate::worldgen::galaxy_gen::{explain_stars, find_stars};
+
+    #[test]
+    fn test_adaptive_order() {
+        let game = GameDesc {
+            star_count: 16,
+            ..Default::default()
+        };
+        // `PlanetCount` has the lower static priority but never rejects anything.
+        let config = r#"{
+            "type": "And",
+            "rules": [
+                { "type": "ThemeId", "themeIds": [8] },
+                { "type": "PlanetCount", "excludeGiant": false, "condition": { "type": "Gte", "value": 1 } }
+            ]
+        }"#;
+        let mut entries = vec![];
+        let adaptive =
+            transform_rules_adaptive(serde_json::from_str(config).unwrap(), &mut entries, 0);
+        let fixed = transform_rules(serde_json::from_str(config).unwrap());
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            explain_stars(0, &game, adaptive.as_ref()).children[0].rule,
+            "PlanetCount"
+        );
+
+        for seed in 0..200 {
+            assert_eq!(
+                find_stars(seed, &game, adaptive.as_ref()),
+                find_stars(seed, &game, fixed.as_ref())
+            );
+        }
+        adaptive.adapt();
+        assert_eq!(
+            explain_stars(0, &game, adaptive.as_ref()).children[0].rule,
+            "ThemeId"
+        );
+        assert_eq!(entries[1].stats.pass_rate(), 1.0);
+    }
+
+    #[test]
+    fn test_adaptive_composite_order() {
+        let game = GameDesc {
+            star_count: 16,
+            ..Default::default()
+        };
+        // Every galaxy has a planet, so `PlanetCount` never decides the galaxy.
+        let config = r#"{
+            "type": "CompositeAnd",
+            "rules": [
+                { "type": "ThemeId", "themeIds": [8] },
+                { "type": "PlanetCount", "excludeGiant": false, "condition": { "type": "Gte", "value": 1 } }
+            ]
+        }"#;
+        let mut entries = vec![];
+        let adaptive =
+            transform_rules_adaptive(serde_json::from_str(config).unwrap(), &mut entries, 0);
+        let fixed = transform_rules(serde_json::from_str(config).unwrap());
+        assert_eq!(
+            explain_stars(0, &game, adaptive.as_ref()).children[0].rule,
+            "PlanetCount"
+        );
+
+        for seed in 0..200 {
+            assert_eq!(
+                find_stars(seed, &game, adaptive.as_ref()),
+                find_stars(seed, &game, fixed.as_ref())
+            );
+        }
+        adaptive.adapt();
+        let explanation = explain_stars(0, &game, adaptive.as_ref());
+        assert_eq!(explanation.rule, "CompositeAnd");
+        assert_eq!(explanation.children[0].rule, "ThemeId");
+        assert_eq!(entries[1].stats.hit_rate(), 1.0);
+        assert!(entries[0].stats.hit_rate() < 1.0);
+    }
+}
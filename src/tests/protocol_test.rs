@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+    use crate::control::SearchControl;
+    use crate::data::game_desc::GameDesc;
+    use crate::scheduler::Scheduler;
+    use crate::server::{handle_message, run_batch, Connection, IncomingMessage, PROTOCOL_VERSION};
+    use serde_json::Value;
+    use std::sync::Arc;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+
+    fn game(star_count: usize) -> GameDesc {
+        GameDesc {
+            star_count,
+            ..Default::default()
+        }
+    }
+
+    fn reply(text: &str) -> Value {
+        let msg: IncomingMessage = serde_json::from_str(text).unwrap();
+        let (tx, _rx) = mpsc::channel::<Message>(10);
+        let mut connection = Connection::new(Scheduler::new(1));
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let resp = runtime.block_on(handle_message(msg, &mut connection, &tx));
+        serde_json::from_str(&resp.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_validate_game() {
+        assert!(game(1024).validate().is_ok());
+        assert!(game(0).validate().is_err());
+        assert!(game(1025).validate().is_err());
+        let mut desc = game(32);
+        desc.resource_multiplier = f32::NAN;
+        assert!(desc.validate().is_err());
+        desc.resource_multiplier = 1.0;
+        desc.hive_max_density = -1.0;
+        assert!(desc.validate().is_err());
+    }
+
+    #[test]
+    fn test_hello() {
+        let resp = reply(r#"{"type":"Hello","version":1}"#);
+        assert_eq!(resp["type"], "Hello");
+        assert_eq!(resp["version"], PROTOCOL_VERSION);
+        let capabilities = resp["capabilities"].as_array().unwrap();
+        assert!(capabilities.iter().any(|c| c == "SearchStarBatch"));
+    }
+
+    #[test]
+    fn test_hello_unsupported_version() {
+        let text = format!(r#"{{"type":"Hello","version":{}}}"#, PROTOCOL_VERSION + 1);
+        let resp = reply(&text);
+        assert_eq!(resp["type"], "Error");
+        assert_eq!(resp["code"], "UnsupportedVersion");
+        assert_eq!(resp["request"], "Hello");
+        let resp = reply(r#"{"type":"Hello"}"#);
+        assert_eq!(resp["type"], "Hello");
+    }
+
+    #[test]
+    fn test_invalid_game_is_an_error() {
+        let text = serde_json::json!({
+            "type": "GenerateBatch",
+            "id": 3,
+            "seeds": [1],
+            "game": game(2000),
+        });
+        let resp = reply(&text.to_string());
+        assert_eq!(resp["type"], "Error");
+        assert_eq!(resp["code"], "InvalidGame");
+        assert_eq!(resp["request"], "GenerateBatch");
+        assert_eq!(resp["id"], 3);
+    }
+
+    #[test]
+    fn test_batch_reports_failed_seed() {
+        let (tx, mut rx) = mpsc::channel::<Message>(10);
+        let scheduler = Scheduler::new(1);
+        let control = Arc::new(SearchControl::default());
+        let queue = scheduler.register(1, Arc::clone(&control));
+        run_batch(&scheduler, queue, 5, vec![1], &tx, &control.token(), |_| {
+            panic!("boom")
+        });
+        let Some(Message::Text(text)) = rx.blocking_recv() else {
+            panic!("expected an error reply");
+        };
+        let resp: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(resp["code"], "Internal");
+        assert_eq!(resp["id"], 5);
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use crate::control::SearchControl;
+    use crate::scheduler::{Job, Scheduler, JOB_SIZE};
+    use std::sync::mpsc;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[test]
+    fn test_queues_share_threads_by_concurrency() {
+        let scheduler = Scheduler::new(1);
+        let order = Arc::new(Mutex::new(vec![]));
+        let (tx, rx) = mpsc::channel();
+        let mut controls = vec![];
+        for (name, concurrency, count) in [('a', 1, 3), ('b', 2, 6)] {
+            let control = Arc::new(SearchControl::default());
+            control.pause();
+            let queue = scheduler.register(concurrency, Arc::clone(&control));
+            let jobs = (0..count)
+                .map(|_| {
+                    let order = Arc::clone(&order);
+                    let tx = tx.clone();
+                    Box::new(move || {
+                        order.lock().unwrap().push(name);
+                        tx.send(()).unwrap();
+                    }) as Job
+                })
+                .collect();
+            scheduler.submit(queue, jobs);
+            controls.push(control);
+        }
+        for control in &controls {
+            control.resume();
+        }
+        scheduler.dispatch();
+        for _ in 0..9 {
+            rx.recv().unwrap();
+        }
+        let order: String = order.lock().unwrap().iter().collect();
+        assert_eq!(order, "abbabbabb");
+    }
+
+    #[test]
+    fn test_batch_results_keep_input_order() {
+        let scheduler = Scheduler::new(4);
+        let queue = scheduler.register(4, Arc::new(SearchControl::default()));
+        let (tx, rx) = mpsc::channel();
+        let seeds: Vec<i32> = (0..(JOB_SIZE as i32 * 5 + 3)).collect();
+        scheduler.submit_batch(
+            queue,
+            seeds.clone(),
+            |seeds| seeds.into_iter().filter(|seed| seed % 3 == 0).collect(),
+            move |result| tx.send(result).unwrap(),
+        );
+        let expected: Vec<i32> = seeds.into_iter().filter(|seed| seed % 3 == 0).collect();
+        assert_eq!(rx.recv().unwrap(), Some(expected));
+
+        let (tx, rx) = mpsc::channel();
+        scheduler.submit_batch(
+            queue,
+            (0..100).collect(),
+            |seeds| {
+                assert!(!seeds.contains(&70));
+                seeds
+            },
+            move |result| tx.send(result).unwrap(),
+        );
+        assert_eq!(rx.recv().unwrap(), None);
+    }
+
+    #[test]
+    fn test_closed_paused_queue_drains() {
+        let scheduler = Scheduler::new(1);
+        let control = Arc::new(SearchControl::default());
+        let queue = scheduler.register(1, Arc::clone(&control));
+        control.pause();
+        let (tx, rx) = mpsc::channel();
+        let token = control.token();
+        scheduler.submit_batch(
+            queue,
+            (0..(JOB_SIZE as i32 * 3)).collect(),
+            move |seeds| {
+                if token.is_cancelled() {
+                    vec![]
+                } else {
+                    seeds
+                }
+            },
+            move |result| tx.send(result).unwrap(),
+        );
+        assert!(rx.recv_timeout(Duration::from_millis(50)).is_err());
+
+        // Closing a session cancels it, which also resumes it.
+        control.cancel();
+        scheduler.unregister(queue);
+        scheduler.dispatch();
+        let result = rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(result, Some(vec![]));
+    }
+}
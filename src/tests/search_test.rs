@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod tests {
+    use crate::search::{ScoredSeed, TopK};
+
+    #[test]
+    fn test_top_k() {
+        let mut top = TopK::new(3);
+        for (seed, score) in [(1, 5.0), (2, 1.0), (3, 7.0), (4, 5.0), (5, 2.0), (6, 7.0)] {
+            top.push(ScoredSeed { seed, score });
+        }
+        let seeds: Vec<i32> = top.to_sorted_vec().iter().map(|s| s.seed).collect();
+        assert_eq!(seeds, vec![3, 6, 1]);
+    }
+}
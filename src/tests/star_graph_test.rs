@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::bitset::StarMask;
+    use std::cell::Cell;
+
+    use crate::data::enums::{SpectrType, StarType};
+    use crate::data::game_desc::GameDesc;
+    use crate::transform_rules::transform_rules;
+    use crate::worldgen::galaxy_gen::{create_galaxy, find_stars};
+
+    fn game() -> GameDesc {
+        GameDesc::default()
+    }
+
+    #[test]
+    fn test_star_graph() {
+        let game = game();
+        let habitable_count = Cell::new(0_i32);
+        let galaxy = create_galaxy(3, &game, &habitable_count);
+        let graph = galaxy.get_graph();
+        for (index, sp) in galaxy.stars.iter().enumerate() {
+            let within = galaxy
+                .stars
+                .iter()
+                .filter(|other| {
+                    other.star.index != index
+                        && sp.star.position.distance_from(&other.star.position) <= 10.0
+                })
+                .count();
+            assert_eq!(graph.count_within(index, 10.0), within);
+            assert!(graph.nearest_distance(index, 1) <= graph.nearest_distance(index, 2));
+        }
+        let hops = graph.hop_counts(0, f64::INFINITY);
+        assert_eq!(hops[0], Some(0));
+        assert!(hops[1..].iter().all(|h| *h == Some(1)));
+        assert!(graph.hop_counts(0, 0.0)[1..].iter().all(|h| h.is_none()));
+    }
+
+    #[test]
+    fn test_nearest() {
+        // An O star within 8 ly of a neutron star that is within 15 ly of birth.
+        let rule = transform_rules(
+            serde_json::from_str(
+                r#"{
+                    "type": "And",
+                    "rules": [
+                        { "type": "Spectr", "spectr": ["O"] },
+                        {
+                            "type": "Nearest",
+                            "rule": {
+                                "type": "And",
+                                "rules": [
+                                    { "type": "StarType", "starType": ["NeutronStar"] },
+                                    { "type": "BirthDistance", "condition": { "type": "Lte", "value": 15 } }
+                                ]
+                            },
+                            "condition": { "type": "Lte", "value": 8 }
+                        }
+                    ]
+                }"#,
+            )
+            .unwrap(),
+        );
+        let game = game();
+        for seed in 0..50 {
+            let habitable_count = Cell::new(0_i32);
+            let galaxy = create_galaxy(seed, &game, &habitable_count);
+            let mut expected = StarMask::new();
+            for sp in &galaxy.stars {
+                if sp.star.get_spectr() != SpectrType::O {
+                    continue;
+                }
+                let found = galaxy.stars.iter().any(|other| {
+                    other.star.index != sp.star.index
+                        && other.star.star_type == StarType::NeutronStar
+                        && other.star.position.magnitude() <= 15.0
+                        && other.star.position.distance_from(&sp.star.position) <= 8.0
+                });
+                if found {
+                    expected.insert(sp.star.index);
+                }
+            }
+            assert_eq!(find_stars(seed, &game, rule.as_ref()), expected);
+        }
+    }
+}
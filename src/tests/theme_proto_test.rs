@@ -0,0 +1,74 @@
+#[cfg(test)]
+mod tests {
+    use crate::data::theme_proto::{get_theme_protos, parse_theme_protos, ThemeProto};
+    use serde_json::{json, Value};
+
+    /// A theme in the format of the game's `ThemeProtoSet.json`.
+    fn dump(theme: &ThemeProto) -> Value {
+        json!({
+            "ID": theme.id,
+            "Name": theme.name,
+            "WaterItemId": theme.water_item_id,
+            "Wind": theme.wind,
+            "Distribute": theme.distribute as i32,
+            "Temperature": theme.temperature,
+            "PlanetType": theme.planet_type as i32,
+            "VeinSpot": theme.vein_spot,
+            "VeinCount": theme.vein_count,
+            "VeinOpacity": theme.vein_opacity,
+            "RareVeins": theme.rare_veins.iter().map(|&vein| vein as i32).collect::<Vec<_>>(),
+            "RareSettings": theme.rare_settings,
+            "GasItems": theme.gas_items,
+            "GasSpeeds": theme.gas_speeds,
+            "Algos": theme.algos,
+            "ModX": { "x": theme.mod_x.0, "y": theme.mod_x.1 },
+            "ModY": { "x": theme.mod_y.0, "y": theme.mod_y.1 },
+        })
+    }
+
+    #[test]
+    fn test_parse_compiled_table() {
+        let compiled = get_theme_protos();
+        let array: Vec<Value> = compiled.iter().map(dump).collect();
+        let set = json!({ "m_Structure": { "dataArray": array } });
+        for json in [Value::from(array), set] {
+            let parsed = parse_theme_protos(&json.to_string()).unwrap();
+            assert_eq!(parsed.len(), compiled.len());
+            for (theme, expected) in parsed.iter().zip(compiled) {
+                assert_eq!(theme.id, expected.id);
+                assert_eq!(theme.name, expected.name);
+                assert_eq!(theme.distribute, expected.distribute);
+                assert_eq!(theme.planet_type, expected.planet_type);
+                assert_eq!(theme.vein_opacity, expected.vein_opacity);
+                assert_eq!(theme.rare_veins, expected.rare_veins);
+                assert_eq!(theme.rare_settings, expected.rare_settings);
+                assert_eq!(theme.algos, expected.algos);
+                assert_eq!(theme.mod_y, expected.mod_y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_theme_protos() {
+        let theme = dump(&get_theme_protos()[0]);
+        let error = |change: Value| {
+            let mut theme = theme.clone();
+            theme
+                .as_object_mut()
+                .unwrap()
+                .extend(change.as_object().unwrap().clone());
+            parse_theme_protos(&json!([theme]).to_string()).unwrap_err()
+        };
+        assert!(error(json!({ "VeinSpot": [1, 2, 3] })).contains("VeinSpot"));
+        assert!(error(json!({ "VeinOpacity": [] })).contains("VeinOpacity"));
+        assert!(error(json!({ "RareSettings": [0.0, 1.0, 0.3] })).contains("RareSettings"));
+        assert!(error(json!({ "Algos": [15] })).contains("Unknown algorithm 15"));
+        assert!(error(json!({ "PlanetType": 6 })).contains("PlanetType"));
+        assert!(error(json!({ "RareVeins": [15] })).contains("rare vein"));
+        assert!(parse_theme_protos(&json!([theme, theme]).to_string())
+            .unwrap_err()
+            .contains("defined twice"));
+        assert!(parse_theme_protos("[]").is_err());
+        assert!(parse_theme_protos(r#"{ "ID": 1 }"#).is_err());
+    }
+}
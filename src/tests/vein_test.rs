@@ -0,0 +1,94 @@
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use crate::data::enums::VeinType;
+    use crate::data::game_desc::GameDesc;
+    use crate::data::rule::{Condition, Rule};
+    use crate::rules::vein_birth_distance::RuleVeinBirthDistance;
+    use crate::rules::vein_cluster::RuleVeinCluster;
+    use crate::rules::vein_pair_distance::RuleVeinPairDistance;
+    use crate::view::{GalaxyView, ViewOptions};
+    use crate::worldgen::galaxy_gen::{create_galaxy, find_stars};
+
+    #[test]
+    fn test_vein_groups_match_actual_veins() {
+        let game = GameDesc {
+            star_count: 16,
+            use_actual_veins: true,
+            ..Default::default()
+        };
+        let habitable_count = Cell::new(0_i32);
+        let galaxy = create_galaxy(1, &game, &habitable_count);
+        let other_count = Cell::new(0_i32);
+        let other = create_galaxy(1, &game, &other_count);
+
+        let mut groups = 0;
+        for (sp, other_sp) in galaxy.stars.iter().zip(other.stars.iter()) {
+            for (planet, other_planet) in sp.get_planets().iter().zip(other_sp.get_planets()) {
+                let vein_groups = planet.get_vein_groups();
+                for vein in other_planet.get_actual_veins() {
+                    let amount: i32 = vein_groups
+                        .iter()
+                        .filter(|group| group.vein_type == vein.vein_type)
+                        .flat_map(|group| group.nodes.iter())
+                        .map(|node| node.amount)
+                        .sum();
+                    assert_eq!(amount, vein.amount);
+                }
+                for group in vein_groups {
+                    assert!(!group.nodes.is_empty());
+                    assert!((-90.0..=90.0).contains(&group.latitude));
+                    assert!((-180.0..=180.0).contains(&group.longitude));
+                }
+                groups += vein_groups.len();
+            }
+        }
+        assert!(groups > 0);
+
+        let options = ViewOptions { vein_groups: true };
+        let json = serde_json::to_value(GalaxyView::new(&galaxy, options)).unwrap();
+        let planet = &json["stars"][0]["planets"][0];
+        assert!(planet["veinGroups"][0]["nodes"][0]["amount"].is_number());
+    }
+
+    #[test]
+    fn test_vein_layout_rules() {
+        let game = GameDesc {
+            star_count: 16,
+            use_actual_veins: true,
+            ..Default::default()
+        };
+        let habitable_count = Cell::new(0_i32);
+        let galaxy = create_galaxy(1, &game, &habitable_count);
+        let birth = &galaxy.stars[0];
+
+        // The starting iron and copper veins are generated next to the landing point.
+        let iron = RuleVeinBirthDistance {
+            vein: VeinType::Iron,
+            condition: Condition::Lte(40.0),
+        };
+        let distance = iron.measure(&galaxy, birth).unwrap();
+        assert!(distance <= 40.0);
+        assert!(iron.measure(&galaxy, &galaxy.stars[1]).is_none());
+        let pair = RuleVeinPairDistance {
+            veins: [VeinType::Iron, VeinType::Copper],
+            condition: Condition::Lte(60.0),
+        };
+        assert!(pair.measure(&galaxy, birth).unwrap() <= 60.0);
+        let cluster = RuleVeinCluster {
+            vein: VeinType::Iron,
+            angle: 5.0,
+            condition: Condition::Gte(6.0),
+        };
+        assert!(cluster.measure(&galaxy, birth).unwrap() >= 6.0);
+
+        for rule in [
+            &iron as &(dyn Rule + Send + Sync),
+            &pair as &(dyn Rule + Send + Sync),
+            &cluster as &(dyn Rule + Send + Sync),
+        ] {
+            assert!(find_stars(1, &game, rule).contains(0));
+        }
+    }
+}
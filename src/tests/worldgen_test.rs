@@ -2,17 +2,14 @@
 mod tests {
     use std::cell::Cell;
 
-    use crate::data::game_desc::GameDesc;
+    use crate::data::game_desc::{GameDesc, GameVersion};
     use crate::worldgen::galaxy_gen::create_galaxy;
 
     #[test]
     fn test_worldgen() {
         let game = GameDesc {
-            star_count: 64,
-            resource_multiplier: 1.0,
-            hive_initial_colonize: 1.0,
-            hive_max_density: 1.0,
             use_actual_veins: true,
+            ..Default::default()
         };
         let habitable_count = Cell::new(0_i32);
         let galaxy = create_galaxy(1, &game, &habitable_count);
@@ -25,4 +22,24 @@ mod tests {
             .unwrap()
             .get_actual_veins();
     }
+
+    #[test]
+    fn test_game_version() {
+        let game: GameDesc = serde_json::from_str(r#"{ "starCount": 32 }"#).unwrap();
+        assert_eq!(game.version, GameVersion::V0_10);
+        let old: GameDesc =
+            serde_json::from_str(r#"{ "starCount": 32, "version": "0.9" }"#).unwrap();
+        assert_eq!(old.version, GameVersion::V0_9);
+
+        let galaxy = crate::create_galaxy(5, &game);
+        let mut old_galaxy = crate::create_galaxy(5, &old);
+        assert!(galaxy.stars.iter().any(|star| star.initial_hive_count > 0));
+        for (star, old_star) in galaxy.stars.iter().zip(&mut old_galaxy.stars) {
+            assert_eq!(old_star.initial_hive_count, 0);
+            assert_eq!(old_star.max_hive_count, 0);
+            old_star.initial_hive_count = star.initial_hive_count;
+            old_star.max_hive_count = star.max_hive_count;
+        }
+        assert_eq!(galaxy, old_galaxy);
+    }
 }
@@ -0,0 +1,54 @@
+use crate::data::planet_rule::PlanetRule;
+use crate::planet_rules;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PlanetRules {
+    And { rules: Vec<PlanetRules> },
+    Or { rules: Vec<PlanetRules> },
+    GasGiant,
+    Satellite,
+    TidalLock,
+    ThemeId(planet_rules::theme_id::PlanetRuleThemeId),
+    OceanType(planet_rules::ocean_type::PlanetRuleOceanType),
+    GasRate(planet_rules::gas_rate::PlanetRuleGasRate),
+    VeinAmount(planet_rules::vein_amount::PlanetRuleVeinAmount),
+    OrbitalPeriod(planet_rules::orbital_period::PlanetRuleOrbitalPeriod),
+    RotationPeriod(planet_rules::rotation_period::PlanetRuleRotationPeriod),
+    Obliquity(planet_rules::obliquity::PlanetRuleObliquity),
+    Resonance(planet_rules::resonance::PlanetRuleResonance),
+    LandFraction(planet_rules::land_fraction::PlanetRuleLandFraction),
+    EquatorLandFraction(planet_rules::equator_land_fraction::PlanetRuleEquatorLandFraction),
+}
+
+pub fn sort_planet_rules(rules: Vec<PlanetRules>) -> Vec<Box<dyn PlanetRule + Send + Sync>> {
+    let mut result: Vec<Box<dyn PlanetRule + Send + Sync>> =
+        rules.into_iter().map(transform_planet_rules).collect();
+    result.sort_by_key(|rule| rule.get_priority());
+    result
+}
+
+pub fn transform_planet_rules(r: PlanetRules) -> Box<dyn PlanetRule + Send + Sync> {
+    match r {
+        PlanetRules::And { rules } => Box::new(planet_rules::and::PlanetRuleAnd {
+            rules: sort_planet_rules(rules),
+        }),
+        PlanetRules::Or { rules } => Box::new(planet_rules::or::PlanetRuleOr {
+            rules: sort_planet_rules(rules),
+        }),
+        PlanetRules::GasGiant => Box::new(planet_rules::gas_giant::PlanetRuleGasGiant),
+        PlanetRules::Satellite => Box::new(planet_rules::satellite::PlanetRuleSatellite),
+        PlanetRules::TidalLock => Box::new(planet_rules::tidal_lock::PlanetRuleTidalLock),
+        PlanetRules::ThemeId(rule) => Box::new(rule),
+        PlanetRules::OceanType(rule) => Box::new(rule),
+        PlanetRules::GasRate(rule) => Box::new(rule),
+        PlanetRules::VeinAmount(rule) => Box::new(rule),
+        PlanetRules::OrbitalPeriod(rule) => Box::new(rule),
+        PlanetRules::RotationPeriod(rule) => Box::new(rule),
+        PlanetRules::Obliquity(rule) => Box::new(rule),
+        PlanetRules::Resonance(rule) => Box::new(rule),
+        PlanetRules::LandFraction(rule) => Box::new(rule),
+        PlanetRules::EquatorLandFraction(rule) => Box::new(rule),
+    }
+}
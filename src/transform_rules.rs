@@ -1,5 +1,7 @@
 use crate::data::rule::{Condition, Rule};
 use crate::rules;
+use crate::transform_planet_rules::{transform_planet_rules, PlanetRules};
+use crate::transform_scores::{transform_scores, Scores};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,9 +23,24 @@ pub enum Rules {
     Or {
         rules: Vec<Rules>,
     },
+    Aggregate {
+        score: Box<Scores>,
+        condition: Condition,
+    },
+    Planet {
+        rule: Box<PlanetRules>,
+        condition: Condition,
+    },
+    Nearest {
+        rule: Box<Rules>,
+        condition: Condition,
+    },
     Luminosity(rules::luminosity::RuleLuminosity),
     DysonRadius(rules::dyson_radius::RuleDysonRadius),
     AverageVeinAmount(rules::average_vein_amount::RuleAverageVeinAmount),
+    VeinCluster(rules::vein_cluster::RuleVeinCluster),
+    VeinBirthDistance(rules::vein_birth_distance::RuleVeinBirthDistance),
+    VeinPairDistance(rules::vein_pair_distance::RuleVeinPairDistance),
     Spectr(rules::spectr::RuleSpectr),
     TidalLockCount(rules::tidal_lock_count::RuleTidalLockCount),
     OceanType(rules::ocean_type::RuleOceanType),
@@ -39,6 +56,9 @@ pub enum Rules {
     GasRate(rules::gas_rate::RuleGasRate),
     PlanetInDysonCount(rules::planet_in_dyson_count::RulePlanetInDysonCount),
     HiveCount(rules::hive_count::RuleHiveCount),
+    NearestNeighbor(rules::nearest_neighbor::RuleNearestNeighbor),
+    NeighborCount(rules::neighbor_count::RuleNeighborCount),
+    HopCount(rules::hop_count::RuleHopCount),
 }
 
 pub fn sort_rules(rules: Vec<Rules>) -> Vec<Box<dyn Rule + Send + Sync>> {
@@ -66,9 +86,24 @@ pub fn transform_rules(r: Rules) -> Box<dyn Rule + Send + Sync> {
         Rules::Or { rules } => Box::new(rules::or::RuleOr {
             rules: sort_rules(rules),
         }),
+        Rules::Aggregate { score, condition } => Box::new(rules::aggregate::RuleAggregate {
+            score: transform_scores(*score),
+            condition,
+        }),
+        Rules::Planet { rule, condition } => Box::new(rules::planet::RulePlanet {
+            rule: transform_planet_rules(*rule),
+            condition,
+        }),
+        Rules::Nearest { rule, condition } => Box::new(rules::nearest::RuleNearest {
+            rule: transform_rules(*rule),
+            condition,
+        }),
         Rules::Luminosity(rule) => Box::new(rule),
         Rules::DysonRadius(rule) => Box::new(rule),
         Rules::AverageVeinAmount(rule) => Box::new(rule),
+        Rules::VeinCluster(rule) => Box::new(rule),
+        Rules::VeinBirthDistance(rule) => Box::new(rule),
+        Rules::VeinPairDistance(rule) => Box::new(rule),
         Rules::Spectr(rule) => Box::new(rule),
         Rules::TidalLockCount(rule) => Box::new(rule),
         Rules::OceanType(rule) => Box::new(rule),
@@ -84,5 +119,8 @@ pub fn transform_rules(r: Rules) -> Box<dyn Rule + Send + Sync> {
         Rules::GasRate(rule) => Box::new(rule),
         Rules::PlanetInDysonCount(rule) => Box::new(rule),
         Rules::HiveCount(rule) => Box::new(rule),
+        Rules::NearestNeighbor(rule) => Box::new(rule),
+        Rules::NeighborCount(rule) => Box::new(rule),
+        Rules::HopCount(rule) => Box::new(rule),
     }
 }
@@ -0,0 +1,47 @@
+use crate::data::score::Score;
+use crate::scores;
+use crate::scores::star_value::StarValue;
+use crate::scores::stars::Aggregate;
+use crate::transform_rules::{transform_rules, Rules};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Scores {
+    Constant {
+        value: f32,
+    },
+    Add {
+        scores: Vec<Scores>,
+    },
+    Multiply {
+        scores: Vec<Scores>,
+    },
+    Stars {
+        aggregate: Aggregate,
+        value: StarValue,
+        #[serde(default)]
+        filter: Option<Rules>,
+    },
+}
+
+pub fn transform_scores(s: Scores) -> Box<dyn Score + Send + Sync> {
+    match s {
+        Scores::Constant { value } => Box::new(scores::arithmetic::ScoreConstant { value }),
+        Scores::Add { scores } => Box::new(scores::arithmetic::ScoreAdd {
+            scores: scores.into_iter().map(transform_scores).collect(),
+        }),
+        Scores::Multiply { scores } => Box::new(scores::arithmetic::ScoreMultiply {
+            scores: scores.into_iter().map(transform_scores).collect(),
+        }),
+        Scores::Stars {
+            aggregate,
+            value,
+            filter,
+        } => Box::new(scores::stars::ScoreStars {
+            aggregate,
+            value,
+            filter: filter.map(transform_rules),
+        }),
+    }
+}
@@ -0,0 +1,164 @@
+use crate::data::enums::{PlanetType, SpectrType, StarType};
+use crate::data::galaxy::Galaxy;
+use crate::data::planet::Planet;
+use crate::data::star_planets::StarWithPlanets;
+use crate::data::vein::{ActualVein, EstimatedVein, VeinGroup};
+use serde::{Deserialize, Serialize};
+
+/// What a [`GalaxyView`] includes besides the values of the game's own
+/// galaxy, so it does not change what is generated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewOptions {
+    /// The position and nodes of every vein as `veinGroups`.
+    #[serde(default)]
+    pub vein_groups: bool,
+}
+
+/// A generated galaxy, with every value computed up front.
+///
+/// Serializes to the same JSON as the `Generate` message of the WebSocket
+/// server and `generate` of the web build.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GalaxyView {
+    pub seed: i32,
+    pub stars: Vec<StarView>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarView {
+    pub index: usize,
+    pub name: String,
+    /// In light years, the birth star is at the origin.
+    pub position: [f64; 3],
+    pub mass: f32,
+    pub lifetime: f32,
+    pub age: f32,
+    pub temperature: f32,
+    #[serde(rename = "type")]
+    pub star_type: StarType,
+    pub spectr: SpectrType,
+    pub luminosity: f32,
+    pub radius: f32,
+    pub dyson_radius: i32,
+    pub initial_hive_count: i32,
+    pub max_hive_count: i32,
+    pub color: f32,
+    pub planets: Vec<PlanetView>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetView {
+    pub index: usize,
+    /// Index of the planet this one is a satellite of.
+    pub orbit_around: Option<usize>,
+    pub orbit_index: usize,
+    pub orbit_radius: f32,
+    pub orbit_inclination: f32,
+    pub orbit_longitude: f32,
+    pub orbital_period: f64,
+    pub obliquity: f32,
+    pub rotation_period: f64,
+    #[serde(rename = "type")]
+    pub planet_type: PlanetType,
+    pub luminosity: f32,
+    pub theme: ThemeView,
+    /// Item id and collection rate of each gas, only for gas giants.
+    pub gases: Vec<(i32, f32)>,
+    /// Only with `ViewOptions::vein_groups`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vein_groups: Option<Vec<VeinGroup>>,
+    /// Only with `use_actual_veins`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual_veins: Option<Vec<ActualVein>>,
+    /// Only without `use_actual_veins`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub veins: Option<Vec<EstimatedVein>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThemeView {
+    pub id: i32,
+    pub name: String,
+    pub wind: f32,
+    pub water_item_id: i32,
+}
+
+impl GalaxyView {
+    pub fn new(galaxy: &Galaxy, options: ViewOptions) -> Self {
+        Self {
+            seed: galaxy.seed,
+            stars: galaxy
+                .stars
+                .iter()
+                .map(|star| StarView::new(star, options))
+                .collect(),
+        }
+    }
+}
+
+impl StarView {
+    pub fn new(star_planets: &StarWithPlanets, options: ViewOptions) -> Self {
+        let star = &star_planets.star;
+        let position = star.position;
+        Self {
+            index: star.index,
+            name: star_planets.name.clone(),
+            position: [position.0, position.1, position.2],
+            mass: star.get_mass(),
+            lifetime: star.get_lifetime(),
+            age: star.get_age(),
+            temperature: star.get_temperature(),
+            star_type: star.star_type,
+            spectr: star.get_spectr(),
+            luminosity: star.get_luminosity(),
+            radius: star.get_radius(),
+            dyson_radius: star.get_dyson_radius(),
+            initial_hive_count: star.get_initial_hive_count(),
+            max_hive_count: star.get_max_hive_count(),
+            color: star.get_color(),
+            planets: star_planets
+                .get_planets()
+                .iter()
+                .map(|planet| PlanetView::new(planet, options))
+                .collect(),
+        }
+    }
+}
+
+impl PlanetView {
+    pub fn new(planet: &Planet, options: ViewOptions) -> Self {
+        let theme = planet.get_theme();
+        let game_desc = planet.star.game_desc;
+        Self {
+            index: planet.index,
+            orbit_around: planet.orbit_around.borrow().map(|p| p.index),
+            orbit_index: planet.orbit_index,
+            orbit_radius: planet.get_orbital_radius(),
+            orbit_inclination: planet.get_orbit_inclination(),
+            orbit_longitude: planet.orbit_longitude,
+            orbital_period: planet.get_orbital_period(),
+            obliquity: planet.get_obliquity(),
+            rotation_period: planet.get_rotation_period(),
+            planet_type: *planet.get_type(),
+            luminosity: planet.get_luminosity(),
+            theme: ThemeView {
+                id: theme.id,
+                name: theme.name.to_string(),
+                wind: theme.wind,
+                water_item_id: theme.water_item_id,
+            },
+            gases: planet.get_gases().clone(),
+            vein_groups: options
+                .vein_groups
+                .then(|| planet.get_vein_groups().clone()),
+            actual_veins: game_desc
+                .use_actual_veins
+                .then(|| planet.get_actual_veins().clone()),
+            veins: (!game_desc.use_actual_veins).then(|| planet.get_estimated_veins().clone()),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use crate::data::game_desc::GameDesc;
+use crate::transform_rules::transform_rules;
+use crate::transform_scores::transform_scores;
+use crate::view::ViewOptions;
+use crate::worldgen::galaxy_diff::diff_seeds;
+use crate::worldgen::galaxy_gen::{explain_stars, find_stars, score_galaxy};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = worldgen)]
+    async fn found(value: JsValue) -> JsValue;
+}
+
+fn game_desc(value: JsValue) -> Result<GameDesc, serde_wasm_bindgen::Error> {
+    let game_desc: GameDesc = serde_wasm_bindgen::from_value(value)?;
+    game_desc
+        .validate()
+        .map_err(serde_wasm_bindgen::Error::new)?;
+    Ok(game_desc)
+}
+
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn generate(
+    seed: JsValue,
+    gameDesc: JsValue,
+    options: JsValue,
+) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    let seed: i32 = serde_wasm_bindgen::from_value(seed)?;
+    let game_desc = game_desc(gameDesc)?;
+    let options: ViewOptions = if options.is_undefined() {
+        ViewOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options)?
+    };
+    let galaxy = crate::create_galaxy_with_options(seed, &game_desc, options);
+    galaxy.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+}
+
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn searchStar(
+    seed: JsValue,
+    gameDesc: JsValue,
+    rule: JsValue,
+) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    let seed: i32 = serde_wasm_bindgen::from_value(seed)?;
+    let game_desc = game_desc(gameDesc)?;
+    let rule = serde_wasm_bindgen::from_value(rule)?;
+    let indexes = crate::find_stars(seed, &game_desc, &crate::transform_rules(rule));
+    serde_wasm_bindgen::to_value(&indexes)
+}
+
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn explain(
+    seed: JsValue,
+    gameDesc: JsValue,
+    rule: JsValue,
+) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    let seed: i32 = serde_wasm_bindgen::from_value(seed)?;
+    let game_desc = game_desc(gameDesc)?;
+    let rule = serde_wasm_bindgen::from_value(rule)?;
+    let transformed = transform_rules(rule);
+    let explanation = explain_stars(seed, &game_desc, transformed.as_ref());
+    explanation.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+}
+
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn diffGalaxies(
+    fromSeed: JsValue,
+    fromGameDesc: JsValue,
+    toSeed: JsValue,
+    toGameDesc: JsValue,
+) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    let from_seed: i32 = serde_wasm_bindgen::from_value(fromSeed)?;
+    let from_game = game_desc(fromGameDesc)?;
+    let to_seed: i32 = serde_wasm_bindgen::from_value(toSeed)?;
+    let to_game = game_desc(toGameDesc)?;
+    let diff = diff_seeds(from_seed, &from_game, to_seed, &to_game);
+    diff.serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+}
+
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn scoreGalaxy(
+    seed: JsValue,
+    gameDesc: JsValue,
+    score: JsValue,
+) -> Result<JsValue, serde_wasm_bindgen::Error> {
+    let seed: i32 = serde_wasm_bindgen::from_value(seed)?;
+    let game_desc = game_desc(gameDesc)?;
+    let score = serde_wasm_bindgen::from_value(score)?;
+    let transformed = transform_scores(score);
+    let value = score_galaxy(seed, &game_desc, None, transformed.as_ref());
+    serde_wasm_bindgen::to_value(&value)
+}
+
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn findStars(
+    gameDesc: JsValue,
+    rule: JsValue,
+    seeds: JsValue,
+) -> Result<(), serde_wasm_bindgen::Error> {
+    let game_desc = game_desc(gameDesc)?;
+    let mut seeds: Vec<i32> = serde_wasm_bindgen::from_value(seeds)?;
+    let rule = serde_wasm_bindgen::from_value(rule)?;
+    spawn_local(async move {
+        let transformed = transform_rules(rule);
+        loop {
+            let mut results: Vec<i32> = vec![];
+            for seed in seeds {
+                let star_indexes = find_stars(seed, &game_desc, transformed.as_ref());
+                if !star_indexes.is_empty() {
+                    results.push(seed);
+                }
+            }
+            let result = serde_wasm_bindgen::to_value(&results).unwrap();
+            let next_batch: JsValue = found(result).await;
+            let next_seeds: Result<Vec<i32>, serde_wasm_bindgen::Error> =
+                serde_wasm_bindgen::from_value(next_batch);
+            match next_seeds {
+                Ok(f) => {
+                    seeds = f;
+                }
+                Err(_) => {
+                    break;
+                }
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,234 @@
+use super::galaxy_gen::create_galaxy;
+use crate::data::enums::{PlanetType, SpectrType, StarType, VeinType};
+use crate::data::galaxy::Galaxy;
+use crate::data::game_desc::GameDesc;
+use crate::data::planet::Planet;
+use crate::data::star_planets::StarWithPlanets;
+use serde::Serialize;
+use std::cell::Cell;
+
+const MAX_VEIN_COUNT: usize = VeinType::Max as usize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Change<T> {
+    pub from: T,
+    pub to: T,
+}
+
+fn change<T: PartialEq>(from: T, to: T) -> Option<Change<T>> {
+    if from == to {
+        None
+    } else {
+        Some(Change { from, to })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VeinDiff {
+    pub vein_type: VeinType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated: Option<Change<f32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub actual: Option<Change<i32>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasDiff {
+    pub item_id: i32,
+    /// 0 when the planet does not have the gas.
+    pub rate: Change<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlanetDiff {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planet_type: Option<Change<PlanetType>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub theme: Option<Change<i32>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub veins: Vec<VeinDiff>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gases: Vec<GasDiff>,
+}
+
+impl PlanetDiff {
+    fn is_empty(&self) -> bool {
+        self.planet_type.is_none()
+            && self.theme.is_none()
+            && self.veins.is_empty()
+            && self.gases.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StarDiff {
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<Change<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub star_type: Option<Change<StarType>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spectr: Option<Change<SpectrType>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_hive_count: Option<Change<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_hive_count: Option<Change<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub planet_count: Option<Change<usize>>,
+    /// Planets are compared by index, as far as both stars have them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub planets: Vec<PlanetDiff>,
+}
+
+impl StarDiff {
+    fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.star_type.is_none()
+            && self.spectr.is_none()
+            && self.initial_hive_count.is_none()
+            && self.max_hive_count.is_none()
+            && self.planet_count.is_none()
+            && self.planets.is_empty()
+    }
+}
+
+/// What changed between two galaxies. Only stars and planets with at least
+/// one change are listed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalaxyDiff {
+    pub seed: Change<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub star_count: Option<Change<usize>>,
+    pub stars: Vec<StarDiff>,
+}
+
+fn estimated_totals(planet: &Planet) -> [f32; MAX_VEIN_COUNT] {
+    let mut totals = [0.0; MAX_VEIN_COUNT];
+    if !planet.is_gas_giant() {
+        for vein in planet.get_estimated_veins() {
+            totals[vein.vein_type as usize] += vein.get_average_amount();
+        }
+    }
+    totals
+}
+
+fn actual_totals(planet: &Planet) -> [i32; MAX_VEIN_COUNT] {
+    let mut totals = [0; MAX_VEIN_COUNT];
+    for vein in planet.get_actual_veins() {
+        totals[vein.vein_type as usize] += vein.amount;
+    }
+    totals
+}
+
+fn diff_gases(from: &Planet, to: &Planet) -> Vec<GasDiff> {
+    let rate = |planet: &Planet, item_id: i32| {
+        planet
+            .get_gases()
+            .iter()
+            .find(|(id, _)| *id == item_id)
+            .map_or(0.0, |(_, rate)| *rate)
+    };
+    let mut item_ids: Vec<i32> = from
+        .get_gases()
+        .iter()
+        .chain(to.get_gases().iter())
+        .map(|(id, _)| *id)
+        .collect();
+    item_ids.sort_unstable();
+    item_ids.dedup();
+    item_ids
+        .into_iter()
+        .filter_map(|item_id| {
+            change(rate(from, item_id), rate(to, item_id)).map(|rate| GasDiff { item_id, rate })
+        })
+        .collect()
+}
+
+/// Actual veins are only compared when either side was generated with
+/// `use_actual_veins`, since they are expensive to compute.
+fn diff_planets(from: &Planet, to: &Planet) -> PlanetDiff {
+    let estimated = (estimated_totals(from), estimated_totals(to));
+    let actual = if from.star.game_desc.use_actual_veins || to.star.game_desc.use_actual_veins {
+        Some((actual_totals(from), actual_totals(to)))
+    } else {
+        None
+    };
+    let veins = (1..MAX_VEIN_COUNT)
+        .filter_map(|index| {
+            let vein = VeinDiff {
+                vein_type: unsafe { ::std::mem::transmute::<i32, VeinType>(index as i32) },
+                estimated: change(estimated.0[index], estimated.1[index]),
+                actual: actual.and_then(|(from, to)| change(from[index], to[index])),
+            };
+            (vein.estimated.is_some() || vein.actual.is_some()).then_some(vein)
+        })
+        .collect();
+    PlanetDiff {
+        index: from.index,
+        planet_type: change(*from.get_type(), *to.get_type()),
+        theme: change(from.get_theme().id, to.get_theme().id),
+        veins,
+        gases: diff_gases(from, to),
+    }
+}
+
+fn diff_stars(from: &StarWithPlanets, to: &StarWithPlanets) -> StarDiff {
+    let from_planets = from.get_planets();
+    let to_planets = to.get_planets();
+    StarDiff {
+        index: from.star.index,
+        name: change(from.name.clone(), to.name.clone()),
+        star_type: change(from.star.star_type, to.star.star_type),
+        spectr: change(from.star.get_spectr(), to.star.get_spectr()),
+        initial_hive_count: change(
+            from.star.get_initial_hive_count(),
+            to.star.get_initial_hive_count(),
+        ),
+        max_hive_count: change(from.star.get_max_hive_count(), to.star.get_max_hive_count()),
+        planet_count: change(from_planets.len(), to_planets.len()),
+        planets: from_planets
+            .iter()
+            .zip(to_planets.iter())
+            .map(|(from, to)| diff_planets(from, to))
+            .filter(|planet| !planet.is_empty())
+            .collect(),
+    }
+}
+
+/// Compares two galaxies star by star and planet by planet. Both galaxies must
+/// have their planets loaded, as `create_galaxy` does.
+pub fn diff_galaxies(from: &Galaxy, to: &Galaxy) -> GalaxyDiff {
+    GalaxyDiff {
+        seed: Change {
+            from: from.seed,
+            to: to.seed,
+        },
+        star_count: change(from.stars.len(), to.stars.len()),
+        stars: from
+            .stars
+            .iter()
+            .zip(to.stars.iter())
+            .map(|(from, to)| diff_stars(from, to))
+            .filter(|star| !star.is_empty())
+            .collect(),
+    }
+}
+
+pub fn diff_seeds(
+    from_seed: i32,
+    from_game: &GameDesc,
+    to_seed: i32,
+    to_game: &GameDesc,
+) -> GalaxyDiff {
+    let from_count = Cell::new(0_i32);
+    let from = create_galaxy(from_seed, from_game, &from_count);
+    let to_count = Cell::new(0_i32);
+    let to = create_galaxy(to_seed, to_game, &to_count);
+    diff_galaxies(&from, &to)
+}
@@ -1,9 +1,11 @@
 use super::name_gen::random_name;
+use crate::data::bitset::StarMask;
 use crate::data::enums::{SpectrType, StarType};
 use crate::data::galaxy::Galaxy;
 use crate::data::game_desc::GameDesc;
 use crate::data::random::DspRandom;
-use crate::data::rule::{Evaluation, Rule};
+use crate::data::rule::{Evaluation, Explanation, Rule};
+use crate::data::score::Score;
 use crate::data::star::Star;
 use crate::data::star_planets::StarWithPlanets;
 use crate::data::vector3::Vector3;
@@ -201,17 +203,46 @@ pub fn create_galaxy<'a>(
         sp.load_planets();
     }
 
-    Galaxy { seed, stars }
+    Galaxy::new(seed, stars)
 }
 
-pub fn find_stars(seed: i32, game_desc: &GameDesc, rule: &Box<dyn Rule + Send + Sync>) -> u64 {
+pub fn find_stars(seed: i32, game_desc: &GameDesc, rule: &(dyn Rule + Send + Sync)) -> StarMask {
     let habitable_count = Cell::new(0_i32);
-    let galaxy = Galaxy {
-        seed,
-        stars: generate_stars(seed, game_desc, &habitable_count),
-    };
+    let galaxy = Galaxy::new(seed, generate_stars(seed, game_desc, &habitable_count));
 
     let evaluation = Evaluation::new(game_desc.star_count);
     let result = rule.evaluate(&galaxy, &evaluation);
     result
 }
+
+/// Evaluates one seed like `find_stars` and returns the full evaluation trace.
+pub fn explain_stars(
+    seed: i32,
+    game_desc: &GameDesc,
+    rule: &(dyn Rule + Send + Sync),
+) -> Explanation {
+    let habitable_count = Cell::new(0_i32);
+    let galaxy = Galaxy::new(seed, generate_stars(seed, game_desc, &habitable_count));
+
+    let evaluation = Evaluation::new(game_desc.star_count);
+    rule.explain(&galaxy, &evaluation)
+}
+
+/// Scores one seed, or returns `None` when `rule` is given and no star passes it.
+pub fn score_galaxy(
+    seed: i32,
+    game_desc: &GameDesc,
+    rule: Option<&(dyn Rule + Send + Sync)>,
+    score: &(dyn Score + Send + Sync),
+) -> Option<f32> {
+    let habitable_count = Cell::new(0_i32);
+    let galaxy = Galaxy::new(seed, generate_stars(seed, game_desc, &habitable_count));
+
+    if let Some(rule) = rule {
+        let evaluation = Evaluation::new(game_desc.star_count);
+        if rule.evaluate(&galaxy, &evaluation).is_empty() {
+            return None;
+        }
+    }
+    Some(score.score(&galaxy))
+}
@@ -0,0 +1,144 @@
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BIN: &str = env!("CARGO_BIN_EXE_dsp_seed");
+
+const CONFIG: &str = r#"{
+    "game": { "starCount": 64 },
+    "rule": { "type": "Luminosity", "condition": { "type": "Gte", "value": 1.2 } }
+}"#;
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("dsp_seed_cluster_{}_{}", std::process::id(), name))
+}
+
+fn spawn(args: &[&str]) -> Child {
+    Command::new(BIN)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// Forwards the stderr lines of `child` as they are printed.
+fn stderr_lines(child: &mut Child) -> mpsc::Receiver<String> {
+    let (tx, rx) = mpsc::channel();
+    let stderr = BufReader::new(child.stderr.take().unwrap());
+    thread::spawn(move || {
+        for line in stderr.lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
+}
+
+fn wait_for_line(lines: &mpsc::Receiver<String>, prefix: &str) {
+    let deadline = Instant::now() + Duration::from_secs(60);
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match lines.recv_timeout(timeout) {
+            Ok(line) if line.starts_with(prefix) => return,
+            Ok(_) => {}
+            Err(e) => panic!("no line starting with {:?}: {}", prefix, e),
+        }
+    }
+}
+
+fn wait_success(child: &mut Child) {
+    let deadline = Instant::now() + Duration::from_secs(120);
+    while Instant::now() < deadline {
+        if let Some(status) = child.try_wait().unwrap() {
+            assert!(status.success(), "exited with {}", status);
+            return;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    let _ = child.kill();
+    panic!("still running after 120 seconds");
+}
+
+/// The lines of an output file, sorted by seed.
+fn read_sorted(path: &Path) -> Vec<String> {
+    let mut lines: Vec<String> = std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    lines.sort_by_key(|line| {
+        let value: serde_json::Value = serde_json::from_str(line).unwrap();
+        value["seed"].as_i64().unwrap()
+    });
+    lines
+}
+
+#[test]
+fn test_killed_worker_lease_is_searched_again() {
+    let config = temp_path("config.json");
+    let merged = temp_path("merged.jsonl");
+    let expected = temp_path("expected.jsonl");
+    std::fs::write(&config, CONFIG).unwrap();
+    let config_arg = config.to_str().unwrap();
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let address = format!("127.0.0.1:{}", port);
+
+    let mut coordinator = spawn(&[
+        "coordinate",
+        "--config",
+        config_arg,
+        "--end",
+        "2000",
+        "--lease",
+        "400",
+        "--lease-timeout",
+        "3",
+        "--bind",
+        &address,
+        "--output",
+        merged.to_str().unwrap(),
+    ]);
+    let coordinator_lines = stderr_lines(&mut coordinator);
+    wait_for_line(&coordinator_lines, "Coordinating seeds");
+
+    // The first worker is killed while it searches its first lease.
+    let mut first = spawn(&["work", "--coordinator", &address, "--threads", "1"]);
+    wait_for_line(&coordinator_lines, "Worker 0 searches on");
+    thread::sleep(Duration::from_millis(200));
+    first.kill().unwrap();
+    first.wait().unwrap();
+    wait_for_line(&coordinator_lines, "Worker 0 disconnected");
+
+    let mut second = spawn(&["work", "--coordinator", &address, "--threads", "2"]);
+    wait_success(&mut coordinator);
+    wait_success(&mut second);
+
+    let mut search = spawn(&[
+        "search",
+        "--config",
+        config_arg,
+        "--end",
+        "2000",
+        "--output",
+        expected.to_str().unwrap(),
+    ]);
+    wait_success(&mut search);
+
+    let merged_lines = read_sorted(&merged);
+    let expected_lines = read_sorted(&expected);
+    for path in [&config, &merged, &expected] {
+        std::fs::remove_file(path).unwrap();
+    }
+    assert!(!expected_lines.is_empty());
+    assert_eq!(merged_lines, expected_lines);
+}
@@ -22,6 +22,11 @@ declare global {
         hiveInitialColonize: float
         hiveMaxDensity: float
         useActualVeins: boolean
+        version?: "0.9" | "0.10"
+    }
+
+    declare interface ViewOptions {
+        veinGroups?: boolean
     }
 
     declare interface Galaxy {
@@ -75,6 +80,7 @@ declare global {
         luminosity: float
         theme: ThemeProto
         gases: Gas[]
+        veinGroups?: VeinGroup[]
     } & PlanetVeins
 
     declare interface ThemeProto {
@@ -99,6 +105,20 @@ declare global {
         amount: integer
     }
 
+    declare interface VeinNode {
+        offset: [x: float, y: float]
+        latitude: float
+        longitude: float
+        amount: integer
+    }
+
+    declare interface VeinGroup {
+        veinType: VeinType
+        latitude: float
+        longitude: float
+        nodes: VeinNode[]
+    }
+
     declare interface VeinStat {
         veinType: VeinType
         min: integer
@@ -80,13 +80,16 @@ function createSender(
 }
 
 async function send(ws: WebSocket, msg: any) {
-    const promise = new Promise<any>((resolve) => {
+    const promise = new Promise<any>((resolve, reject) => {
         const listener = (ev: MessageEvent) => {
             if (typeof ev.data === "string") {
                 const resp = JSON.parse(ev.data)
                 if (resp.type === msg.type) {
                     ws.removeEventListener("message", listener)
                     resolve(JSON.parse(ev.data))
+                } else if (resp.type === "Error" && resp.request === msg.type) {
+                    ws.removeEventListener("message", listener)
+                    reject(new Error(resp.message))
                 }
             }
         }
@@ -98,6 +101,7 @@ async function send(ws: WebSocket, msg: any) {
 
 export class WorldGenNative implements WorldGen {
     private stopped: boolean = false
+    private searching?: WebSocket
 
     async generate(seed: integer, gameDesc: GameParameters): Promise<Galaxy> {
         const ws = await connect()
@@ -137,6 +141,7 @@ export class WorldGenNative implements WorldGen {
         this.stopped = false
         const batch = generateBatchFromRange(batchSize, nextBatchId, range)
         const ws = await connect()
+        this.searching = ws
         try {
             let running = true
             ws.addEventListener("close", () => {
@@ -175,6 +180,7 @@ export class WorldGenNative implements WorldGen {
                 })
             })
         } finally {
+            this.searching = undefined
             if (ws.readyState === WebSocket.OPEN) {
                 ws.close()
             }
@@ -183,5 +189,8 @@ export class WorldGenNative implements WorldGen {
 
     stop() {
         this.stopped = true
+        if (this.searching?.readyState === WebSocket.OPEN) {
+            this.searching.send(JSON.stringify({ type: "Cancel" }))
+        }
     }
 }